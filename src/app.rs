@@ -1,34 +1,61 @@
-use std::{collections::VecDeque, error::Error};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io::stdout,
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use chrono::{DateTime, Local};
+use crossterm::{
+    event::{self, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::SetTitle,
+    ExecutableCommand as _,
+};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
+    widgets::{Paragraph, Wrap},
     Frame, Terminal,
 };
 
 use crate::{
     client::Client,
+    clipboard,
     config::Config,
-    source::{self, Sources},
+    resume::{self, LastSearch},
+    seen,
+    source::{self, add_protocol, cache, nyaa_html, Item, SearchQuery, SourceResults, Sources},
     widget::{
         self,
         category::CategoryPopup,
         clients::ClientsPopup,
+        confirm::{Confirm, ConfirmPopup},
         error::ErrorPopup,
-        filter::FilterPopup,
+        files::FilesPopup,
+        filter::{Filter, FilterPopup},
         help::HelpPopup,
+        legend::LegendWidget,
         page::PagePopup,
         results::ResultsWidget,
         search::SearchWidget,
+        settings::SettingsPopup,
         sort::{SortDir, SortPopup},
         sources::SourcesPopup,
+        status::StatusWidget,
         theme::{Theme, ThemePopup},
-        Widget,
+        EnumIter, Widget,
     },
 };
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+};
 
 pub static APP_NAME: &str = "nyaa";
+static MIN_WIDTH: u16 = 30;
+static MIN_HEIGHT: u16 = 10;
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum LoadType {
@@ -37,6 +64,21 @@ pub enum LoadType {
     Filtering,
     Categorizing,
     Downloading,
+    DownloadingAll,
+    Resetting,
+    Reloading,
+    Refreshing,
+    Files,
+    TestingClient,
+    ClearingFilters,
+}
+
+// Where focus starts once the app is opened
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartMode {
+    Results,
+    Search,
 }
 
 #[derive(PartialEq, Clone)]
@@ -53,6 +95,9 @@ pub enum Mode {
     Error,
     Page,
     Help,
+    Confirm(Confirm),
+    Files,
+    Settings,
 }
 
 impl ToString for Mode {
@@ -70,12 +115,16 @@ impl ToString for Mode {
             Mode::Error => "Error".to_string(),
             Mode::Page => "Page".to_owned(),
             Mode::Help => "Help".to_string(),
+            Mode::Confirm(_) => "Confirm".to_string(),
+            Mode::Files => "Files".to_string(),
+            Mode::Settings => "Settings".to_string(),
         }
     }
 }
 
 pub struct App {
     pub mode: Mode,
+    pub prev_modes: Vec<Mode>, // Where to go back to when a popup opened over another closes
     pub theme: &'static Theme,
     pub config: Config,
     pub errors: VecDeque<String>,
@@ -85,6 +134,15 @@ pub struct App {
     pub total_results: usize,
     pub src: Sources,
     pub client: Client,
+    pub loading: bool,
+    pub last_request_ok: Option<bool>, // None until the first request finishes
+    pub downloading: Option<(usize, usize)>, // Progress of a batch download
+    pub notice: Option<(String, Instant)>, // Brief message and when it was shown
+    pub offline: bool,                 // Use the bundled sample results instead of nyaa
+    pub dry_run: bool,                 // Describe downloads instead of sending them
+    pub dry_runs: Vec<String>,         // Downloads that would have been sent, printed on exit
+    pub theme_override: Option<String>, // Theme from --theme, used instead of default_theme
+    pub refreshed: Option<DateTime<Local>>,
     should_quit: bool,
 }
 
@@ -92,9 +150,31 @@ impl App {
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
+    pub fn request_quit(&mut self) {
+        match self.config.confirm_quit {
+            true => self.push_mode(Mode::Confirm(Confirm::Quit)),
+            false => self.quit(),
+        }
+    }
+    // Open `mode` over the current one, which `pop_mode` goes back to
+    pub fn push_mode(&mut self, mode: Mode) {
+        self.prev_modes.push(self.mode.to_owned());
+        self.mode = mode;
+    }
+    // Loads already ran, so they aren't started again
+    pub fn pop_mode(&mut self) {
+        self.mode = match self.prev_modes.pop() {
+            Some(Mode::Loading(_)) | None => Mode::Normal,
+            Some(mode) => mode,
+        };
+    }
     pub fn show_error<S: ToString>(&mut self, error: S) {
         self.errors.push_back(error.to_string());
     }
+    // Show a message in the corner of the results for a few seconds
+    pub fn notify<S: ToString>(&mut self, msg: S) {
+        self.notice = Some((msg.to_string(), Instant::now()));
+    }
 }
 
 #[derive(Default)]
@@ -110,12 +190,18 @@ pub struct Widgets {
     pub error: ErrorPopup,
     pub page: PagePopup,
     pub help: HelpPopup,
+    pub confirm: ConfirmPopup,
+    pub files: FilesPopup,
+    pub settings: SettingsPopup,
+    pub status: StatusWidget,
+    pub legend: LegendWidget,
 }
 
 impl Default for App {
     fn default() -> Self {
         App {
             mode: Mode::Loading(LoadType::Searching),
+            prev_modes: vec![],
             theme: widget::theme::THEMES[0],
             config: Config::default(),
             errors: VecDeque::new(),
@@ -125,39 +211,107 @@ impl Default for App {
             total_results: 0,
             src: Sources::NyaaHtml,
             client: Client::Cmd,
+            loading: false,
+            last_request_ok: None,
+            downloading: None,
+            notice: None,
+            offline: false,
+            dry_run: false,
+            dry_runs: vec![],
+            theme_override: None,
+            refreshed: None,
             should_quit: false,
         }
     }
 }
 
-fn help_event(app: &mut App, e: &Event) {
+fn help_event(app: &mut App, e: &Event, prev_mode: &Mode) {
     if let Event::Key(KeyEvent {
         code,
         kind: KeyEventKind::Press,
+        modifiers,
         ..
     }) = e
     {
-        match code {
-            KeyCode::Char('?') if app.mode != Mode::Search => {
-                app.mode = Mode::Help;
+        match (code, modifiers) {
+            (KeyCode::Char('c'), &KeyModifiers::CONTROL) => match *prev_mode {
+                Mode::Confirm(Confirm::Quit) => app.quit(),
+                _ => app.request_quit(),
+            },
+            // Don't reopen help if it was just closed
+            _ if *prev_mode == Mode::Help => {}
+            (KeyCode::Char('?'), _) if *prev_mode != Mode::Search => {
+                app.push_mode(Mode::Help);
             }
-            KeyCode::F(1) => {
-                app.mode = Mode::Help;
+            (KeyCode::F(1), _) => {
+                app.push_mode(Mode::Help);
             }
             _ => {}
         }
     }
 }
 
+// Copy a link to the current results on nyaa's website, to share or open in a browser
+fn copy_search_url(app: &mut App, w: &Widgets, e: &Event) {
+    if let Event::Key(KeyEvent {
+        code: KeyCode::Char('y'),
+        kind: KeyEventKind::Press,
+        modifiers: KeyModifiers::CONTROL,
+        ..
+    }) = e
+    {
+        let mut search = SearchQuery::new(app, w);
+        search.items = vec![];
+        let url = nyaa_html::search_url(&search, &app.config);
+        // The url is in the message, so it can still be copied by hand if the clipboard fails
+        clipboard::copy(app, &url, &url);
+    }
+}
+
 pub fn draw(widgets: &mut Widgets, app: &mut App, f: &mut Frame) {
+    // Below this, popups and the results table can't be laid out sensibly
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        let msg = format!(
+            "Terminal too small\n{}x{} is needed, this is {}x{}",
+            MIN_WIDTH, MIN_HEIGHT, size.width, size.height
+        );
+        f.render_widget(Paragraph::new(msg).wrap(Wrap { trim: true }), size);
+        return;
+    }
+    let status_height = match app.config.status_bar {
+        true => 1,
+        false => 0,
+    };
+    let legend_height = match app.config.key_legend {
+        true => 1,
+        false => 0,
+    };
+    let search_height = match (app.config.dense, app.config.search_border) {
+        (true, _) => 1,
+        (false, true) => 3,
+        (false, false) => 2,
+    };
     let layout = Layout::new(
         Direction::Vertical,
-        [Constraint::Length(3), Constraint::Min(1)],
+        [
+            Constraint::Length(search_height),
+            Constraint::Min(1),
+            Constraint::Length(status_height),
+            Constraint::Length(legend_height),
+        ],
     )
     .split(f.size());
 
     widgets.search.draw(f, app, layout[0]);
+    widgets.results.area = layout[1];
     widgets.results.draw(f, app, layout[1]);
+    if app.config.status_bar {
+        widgets.status.draw(f, app, layout[2]);
+    }
+    if app.config.key_legend {
+        widgets.legend.draw(f, app, layout[3]);
+    }
     match app.mode {
         Mode::Category => widgets.category.draw(f, app, f.size()),
         Mode::Sort(_) => widgets.sort.draw(f, app, f.size()),
@@ -174,10 +328,46 @@ pub fn draw(widgets: &mut Widgets, app: &mut App, f: &mut Frame) {
         Mode::Page => widgets.page.draw(f, app, f.size()),
         Mode::Sources => widgets.sources.draw(f, app, f.size()),
         Mode::Clients => widgets.clients.draw(f, app, f.size()),
+        Mode::Confirm(_) => widgets.confirm.draw(f, app, f.size()),
+        Mode::Files => widgets.files.draw(f, app, f.size()),
+        Mode::Settings => widgets.settings.draw(f, app, f.size()),
         Mode::Normal | Mode::Search | Mode::Loading(_) => {}
     }
 }
 
+fn reset_selections(app: &mut App, w: &mut Widgets) {
+    let default = Config::default();
+    w.sort.selected = default.default_sort;
+    w.filter.selected = default.default_filter;
+    app.ascending = false;
+    app.page = 1;
+    if let Some((i, theme)) = widget::theme::find_theme(default.default_theme) {
+        w.theme.selected = i;
+        app.theme = theme;
+    }
+    if let Some(ent) = widget::category::find_category(default.default_category) {
+        w.category.category = ent.id;
+    }
+}
+
+// Filters applied by the source, so an empty page can say what might be hiding results
+fn active_filters(app: &App, w: &Widgets) -> Vec<String> {
+    let mut filters = vec![];
+    if !matches!(w.filter.selected, Filter::NoFilter) {
+        filters.push(w.filter.selected.to_string());
+    }
+    if w.search.exclude && !app.config.exclude.is_empty() {
+        filters.push(format!("Excluding {}", app.config.exclude.join(", ")));
+    }
+    filters
+}
+
+fn clear_filters(app: &mut App, w: &mut Widgets) {
+    w.filter.selected = Filter::NoFilter;
+    w.search.exclude = false;
+    app.page = 1;
+}
+
 fn get_help(app: &mut App, w: &mut Widgets) {
     let help = match app.mode {
         Mode::Category => CategoryPopup::get_help(),
@@ -189,37 +379,327 @@ fn get_help(app: &mut App, w: &mut Widgets) {
         Mode::Page => PagePopup::get_help(),
         Mode::Sources => SourcesPopup::get_help(),
         Mode::Clients => ClientsPopup::get_help(),
+        Mode::Confirm(_) => ConfirmPopup::get_help(),
+        Mode::Files => FilesPopup::get_help(),
+        Mode::Settings => SettingsPopup::get_help(),
         Mode::Error => None,
         Mode::Help => None,
         Mode::Loading(_) => None,
     };
+    w.legend.items = help.clone().unwrap_or_default();
     if let Some(msg) = help {
         w.help.with_items(msg, app.mode.clone());
         w.help.table.select(0);
     }
 }
 
+// Sent back from a background load
+struct LoadResult {
+    generation: usize, // Results are stale unless this matches the latest load
+    load_type: LoadType,
+    key: Option<String>, // Cache key to store results under
+    result: Result<SourceResults, Box<dyn Error + Send + Sync>>,
+}
+
+// Tracks the load running in the background, if any
+#[derive(Default)]
+struct Loader {
+    generation: usize,
+    task: Option<JoinHandle<()>>,
+    started: Option<Instant>,
+    page: usize,   // Page of the results on screen, restored if a load is cancelled
+    query: String, // Query of the latest load, for the window title
+}
+
+fn apply_results(app: &mut App, w: &mut Widgets, results: SourceResults, load_type: LoadType) {
+    app.last_page = results.last_page;
+    app.total_results = results.total_results;
+    app.page = app.page.clamp(1, app.last_page.max(1));
+    // Shown in the results title, since refreshes would bring a popup back every time
+    w.results.skipped = results.skipped;
+    w.results.filters = active_filters(app, w);
+    w.results.blocked = None;
+    match load_type {
+        LoadType::Refreshing => {
+            w.results.refresh_items(results.items);
+            app.refreshed = Some(Local::now());
+        }
+        _ => {
+            // Only the same search can be compared, so anything else starts over
+            match load_type {
+                LoadType::Reloading => w.results.track_seeders(&results.items),
+                _ => w.results.deltas.clear(),
+            }
+            w.results
+                .with_items(results.items, w.sort.selected.clone(), app.ascending);
+            app.refreshed = None;
+        }
+    }
+}
+
+// Start loading results in the background, cancelling any load already in progress
+fn start_load(
+    app: &mut App,
+    w: &mut Widgets,
+    load_type: LoadType,
+    tx: &UnboundedSender<LoadResult>,
+    loader: &mut Loader,
+) {
+    // Anything still in flight is now outdated
+    loader.generation += 1;
+    if let Some(task) = loader.task.take() {
+        task.abort();
+    }
+    let search = SearchQuery::new(app, w);
+    loader.query = search.query.to_owned();
+    let key = match app.config.cache_results && source::cacheable(app.src, load_type) {
+        true => Some(cache::key(app.src, &app.config.base_url, &search)),
+        false => None,
+    };
+    loader.started = Some(Instant::now());
+    let blocked = app
+        .config
+        .empty_search
+        .blocked(&search.query, search.category);
+    if let Some(reason) = blocked {
+        app.loading = false;
+        app.last_page = 1;
+        app.total_results = 0;
+        w.results
+            .with_items(vec![], w.sort.selected.clone(), app.ascending);
+        w.results.blocked = Some(reason);
+        return;
+    }
+    if !matches!(load_type, LoadType::Reloading | LoadType::Refreshing) {
+        if let Some(results) = key
+            .as_ref()
+            .and_then(|k| cache::read(k, app.config.cache_ttl))
+        {
+            app.loading = false;
+            apply_results(app, w, results, load_type);
+            loader.page = app.page;
+            return;
+        }
+    }
+
+    // Refreshing keeps the current results on screen until the new ones arrive
+    app.loading = load_type != LoadType::Refreshing;
+    let (src, config, tx) = (app.src, app.config.clone(), tx.clone());
+    let generation = loader.generation;
+    loader.task = Some(tokio::spawn(async move {
+        let result = source::load(src, load_type, &search, &config).await;
+        let _ = tx.send(LoadResult {
+            generation,
+            load_type,
+            key,
+            result,
+        });
+    }));
+}
+
+// Count the results of what's typed so far in the background, without loading them
+fn start_count(
+    app: &App,
+    w: &Widgets,
+    tx: &UnboundedSender<(String, usize)>,
+    task: &mut Option<JoinHandle<()>>,
+) {
+    if let Some(task) = task.take() {
+        task.abort();
+    }
+    let mut search = SearchQuery::new(app, w);
+    let blocked = app
+        .config
+        .empty_search
+        .blocked(&search.query, search.category);
+    if app.offline || blocked.is_some() {
+        return;
+    }
+    search.page = 1;
+    search.items = vec![];
+    let (config, tx) = (app.config.clone(), tx.clone());
+    *task = Some(tokio::spawn(async move {
+        // Not worth interrupting typing over, so failures just don't show a count
+        if let Ok(count) = nyaa_html::count(&search, &config).await {
+            let _ = tx.send((search.query, count));
+        }
+    }));
+}
+
+// Shown in the terminal's title bar, to tell windows apart
+fn window_title(app: &App, query: &str) -> String {
+    let src = match app.src {
+        Sources::Fixture => app.src.to_string(),
+        _ => Url::parse(&add_protocol(app.config.base_url.clone(), true))
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_owned()))
+            .unwrap_or(app.config.base_url.to_owned()),
+    };
+    match query.trim() {
+        "" => format!("{} ({})", APP_NAME, src),
+        query => format!("{}: {} ({})", APP_NAME, query, src),
+    }
+}
+
+// Stop the load in progress, going back to the results that were there before it
+fn cancel_load(app: &mut App, loader: &mut Loader) {
+    loader.generation += 1;
+    if let Some(task) = loader.task.take() {
+        task.abort();
+    }
+    app.loading = false;
+    app.page = loader.page.max(1);
+    app.notify("Cancelled loading");
+}
+
+// Items left to download from a batch, spaced out by `download_delay`
+struct Batch {
+    items: Vec<Item>,
+    next_at: Instant,
+    sent: usize, // Downloads that didn't fail, and weren't only a dry run
+}
+
 pub async fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
 ) -> Result<(), Box<dyn Error>> {
     let w = &mut Widgets::default();
     let config = match Config::load() {
-        Ok(config) => config,
+        Ok((config, skipped)) => {
+            if let Some(e) = skipped {
+                app.show_error(e);
+            }
+            config
+        }
         Err(e) => {
             app.show_error(e);
             app.config.clone()
         }
     };
     config.apply(app, w);
+    match seen::load() {
+        Ok(seen) => w.results.seen = seen,
+        Err(e) => app.show_error(format!("Failed to read seen torrents:\n{}", e)),
+    }
+    if app.config.resume_search {
+        match resume::load() {
+            Ok(Some(last)) => {
+                w.search.input.input = last.query;
+                w.search.input.cursor = w.search.input.input.len();
+                app.page = last.page.max(1);
+            }
+            Ok(None) => {}
+            Err(e) => app.show_error(format!("Failed to read last search:\n{}", e)),
+        }
+    }
+    if app.config.mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+    if app.offline {
+        app.src = Sources::Fixture;
+    }
+    let (tx, mut rx) = mpsc::unbounded_channel::<LoadResult>();
+    let mut loader = Loader::default();
+    let (count_tx, mut count_rx) = mpsc::unbounded_channel::<(String, usize)>();
+    let mut count_task: Option<JoinHandle<()>> = None;
+    // Results still load in the background while typing
+    if app.config.start_mode == StartMode::Search {
+        start_load(app, w, LoadType::Searching, &tx, &mut loader);
+        app.mode = Mode::Search;
+    }
+    let mut batch: Option<Batch> = None;
+    let mut title = String::new();
     loop {
         if app.should_quit {
+            if app.config.resume_search {
+                let last = LastSearch {
+                    query: w.search.input.input.to_owned(),
+                    page: app.page,
+                };
+                // The terminal is about to be restored, so there's nowhere to show an error
+                let _ = resume::store(&last);
+            }
             return Ok(());
         }
-        if !app.errors.is_empty() {
-            app.mode = Mode::Error;
+        // Download one item of a batch at a time, so the UI is drawn in between
+        if let Some(b) = batch.as_mut().filter(|b| b.next_at <= Instant::now()) {
+            let total = app
+                .downloading
+                .map(|(_, total)| total)
+                .unwrap_or(b.items.len());
+            let done = total - b.items.len();
+            app.downloading = Some((done + 1, total));
+            terminal.draw(|f| draw(w, app, f))?;
+            // Each failed download adds its own error, so keep going through the rest
+            let item = b.items.remove(0);
+            if app.client.clone().download(&item, app).await {
+                b.sent += 1;
+                w.results.mark_seen(app, seen::key(&item));
+            }
+            b.next_at = Instant::now() + Duration::from_millis(app.config.download_delay);
+            if b.items.is_empty() {
+                if b.sent > 0 {
+                    Client::notify_done(app, &format!("{} torrents", b.sent));
+                }
+                batch = None;
+                app.downloading = None;
+            }
+        }
+        while let Ok(res) = rx.try_recv() {
+            // A newer load was started, so these results would clobber it
+            if res.generation != loader.generation {
+                continue;
+            }
+            app.loading = false;
+            loader.task = None;
+            app.last_request_ok = Some(res.result.is_ok());
+            match res.result {
+                Ok(results) => {
+                    if let Some(Err(e)) = res
+                        .key
+                        .map(|k| cache::write(&k, &results, app.config.cache_ttl))
+                    {
+                        app.show_error(format!("Failed to write results to cache:\n{}", e));
+                    }
+                    apply_results(app, w, results, res.load_type);
+                    loader.page = app.page;
+                }
+                Err(e) => app.show_error(e),
+            }
+        }
+        let refresh = Duration::from_secs(app.config.auto_refresh);
+        if app.config.auto_refresh > 0
+            && app.mode == Mode::Normal
+            && loader.task.is_none()
+            && loader.started.is_some_and(|t| t.elapsed() >= refresh)
+        {
+            start_load(app, w, LoadType::Refreshing, &tx, &mut loader);
+        }
+        if w.search.deadline.is_some_and(|d| d <= Instant::now()) {
+            w.search.deadline = None;
+            app.page = 1;
+            start_load(app, w, LoadType::Searching, &tx, &mut loader);
+        }
+        if w.search.count_deadline.is_some_and(|d| d <= Instant::now()) {
+            w.search.count_deadline = None;
+            start_count(app, w, &count_tx, &mut count_task);
+        }
+        while let Ok(count) = count_rx.try_recv() {
+            w.search.count = Some(count);
+        }
+        // Nothing is open under the results, so anything left over is stale
+        if app.mode == Mode::Normal {
+            app.prev_modes.clear();
+        }
+        if !app.errors.is_empty() && app.mode != Mode::Error {
+            app.push_mode(Mode::Error);
         }
 
+        let new_title = window_title(app, &loader.query);
+        if new_title != title {
+            stdout().execute(SetTitle(&new_title))?;
+            title = new_title;
+        }
         get_help(app, w);
         terminal.draw(|f| draw(w, app, f))?;
         if let Mode::Loading(load_type) = app.mode {
@@ -235,24 +715,113 @@ pub async fn run_app<B: Backend>(
                     Some(i) => i,
                     None => continue,
                 };
-                app.client.clone().download(item, app).await; // TODO: Use user selected client
+                // TODO: Use user selected client
+                if app.client.clone().download(item, app).await {
+                    Client::notify_done(app, &item.title);
+                    let key = seen::key(item);
+                    w.results.mark_seen(app, key);
+                }
                 continue;
             }
-
-            let result = source::load(app.src, load_type, app, w).await;
-
-            match result {
-                Ok(items) => w.results.with_items(items, w.sort.selected.clone()),
-                Err(e) => app.show_error(e),
+            if load_type == LoadType::DownloadingAll {
+                app.downloading = None;
+                batch = Some(Batch {
+                    items: w.results.table.items.to_owned(),
+                    next_at: Instant::now(),
+                    sent: 0,
+                });
+                continue;
+            }
+            if load_type == LoadType::Files {
+                let item = match w
+                    .results
+                    .table
+                    .state
+                    .selected()
+                    .and_then(|i| w.results.table.items.get(i))
+                {
+                    Some(i) => i,
+                    None => continue,
+                };
+                match nyaa_html::get_files(&item.post_link, app.config.timeout).await {
+                    Ok(files) => {
+                        w.files.with_files(item.title.to_owned(), files);
+                        app.mode = Mode::Files;
+                    }
+                    Err(e) => app.show_error(e),
+                }
+                continue;
+            }
+            if load_type == LoadType::TestingClient {
+                app.mode = Mode::Clients;
+                let selected = w.clients.table.state.selected().unwrap_or(0);
+                if let Some(client) = Client::iter().nth(selected) {
+                    match client.test_connection(app).await {
+                        Ok(msg) => app.notify(msg),
+                        Err(e) => app.show_error(e),
+                    }
+                }
+                continue;
+            }
+            if load_type == LoadType::ClearingFilters {
+                clear_filters(app, w);
             }
+            if load_type == LoadType::Resetting {
+                reset_selections(app, w);
+                app.mode = Mode::Confirm(Confirm::SaveDefaults);
+            }
+            // Every result is already here, so only the order needs to change
+            if load_type == LoadType::Sorting
+                && app.last_page <= 1
+                && !app.loading
+                && w.results.flip_sort(&w.sort.selected, app.ascending)
+            {
+                continue;
+            }
+            start_load(app, w, load_type, &tx, &mut loader);
             continue; // Redraw
         }
 
+        // Poll so results from background loads are drawn as soon as they arrive
+        if !event::poll(Duration::from_millis(50))? {
+            continue;
+        }
         let evt = event::read()?;
+        // Esc while results are loading gives up on the load, in case it hangs
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            kind: KeyEventKind::Press,
+            ..
+        }) = evt
+        {
+            if app.loading && app.mode == Mode::Normal {
+                cancel_load(app, &mut loader);
+                continue;
+            }
+        }
+        let prev_mode = app.mode.clone();
         match app.mode {
             Mode::Category => w.category.handle_event(app, &evt),
             Mode::Sort(_) => w.sort.handle_event(app, &evt),
-            Mode::Normal => w.results.handle_event(app, &evt),
+            Mode::Normal => {
+                w.results.handle_event(app, &evt);
+                if app.mode == Mode::Settings {
+                    w.settings.edit(&app.config);
+                }
+                w.category.quick_switch(app, &evt);
+                w.theme.quick_switch(app, &evt);
+                w.search.toggle_exclude(app, &evt);
+                w.search.search_group(app, &evt, w.results.selected());
+                w.sort.quick_sort(app, &evt);
+                copy_search_url(app, w, &evt);
+                if let Some(sort) = w.results.clicked_sort(app, &evt) {
+                    w.sort.sort_by(app, sort);
+                }
+                if let Some((title, lines)) = w.results.debug_info(app, &evt) {
+                    w.files.with_files(title, lines);
+                    app.mode = Mode::Files;
+                }
+            }
             Mode::Search => w.search.handle_event(app, &evt),
             Mode::Filter => w.filter.handle_event(app, &evt),
             Mode::Theme => w.theme.handle_event(app, &evt),
@@ -261,10 +830,11 @@ pub async fn run_app<B: Backend>(
             Mode::Help => w.help.handle_event(app, &evt),
             Mode::Sources => w.sources.handle_event(app, &evt),
             Mode::Clients => w.clients.handle_event(app, &evt),
+            Mode::Confirm(_) => w.confirm.handle_event(app, &evt),
+            Mode::Files => w.files.handle_event(app, &evt),
+            Mode::Settings => w.settings.handle_event(app, &evt),
             Mode::Loading(_) => {}
         }
-        if app.mode != Mode::Help {
-            help_event(app, &evt);
-        }
+        help_event(app, &evt, &prev_mode);
     }
 }
@@ -1,23 +1,61 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    io::{stdout, Write as _},
+    process::{Command, Stdio},
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{app::App, source::Item, widget::EnumIter};
 
+use self::{aria2::Aria2Config, cmd::CmdConfig, qbit::QbitConfig, rest::RestConfig};
+
+pub mod aria2;
 pub mod cmd;
 pub mod qbit;
+pub mod rest;
 
 #[derive(Clone, Copy, Deserialize, Serialize)]
 pub enum Client {
     #[serde(rename = "cmd")]
-    Cmd, // torrent_client_cmd
+    Cmd, // Runs a shell command
     #[serde(rename = "qBittorrent")]
     Qbit, // qBittorrent Web API
+    #[serde(rename = "aria2")]
+    Aria2, // aria2 JSON-RPC
+    #[serde(rename = "rest")]
+    Rest, // Any HTTP request, defined in the config
+}
+
+// Each client type has its own optional table, so new ones can be added without touching
+// existing configs
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ClientConfig {
+    pub default: Client,
+    pub cmd: Option<CmdConfig>,
+    #[serde(rename = "qBittorrent")]
+    pub qbit: Option<QbitConfig>,
+    pub aria2: Option<Aria2Config>,
+    pub rest: Option<RestConfig>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            default: Client::Cmd,
+            cmd: None,
+            qbit: None,
+            aria2: None,
+            rest: None,
+        }
+    }
 }
 
 impl EnumIter<Client> for Client {
     fn iter() -> std::slice::Iter<'static, Client> {
-        static CLIENTS: &[Client] = &[Client::Cmd, Client::Qbit];
+        static CLIENTS: &[Client] = &[Client::Cmd, Client::Qbit, Client::Aria2, Client::Rest];
         CLIENTS.iter()
     }
 }
@@ -27,25 +65,116 @@ impl ToString for Client {
         match *self {
             Self::Cmd => "cmd".to_owned(),
             Self::Qbit => "qBittorrent".to_owned(),
+            Self::Aria2 => "aria2".to_owned(),
+            Self::Rest => "rest".to_owned(),
         }
     }
 }
 
 impl Client {
-    pub async fn download(&self, item: &Item, app: &mut App) {
+    // Returns whether the torrent was actually sent. Failures add their own error
+    pub async fn download(&self, item: &Item, app: &mut App) -> bool {
+        if app.dry_run {
+            let action = match self {
+                Self::Cmd => cmd::dry_run(item, app),
+                Self::Qbit => qbit::dry_run(item, app),
+                Self::Aria2 => aria2::dry_run(item, app),
+                Self::Rest => rest::dry_run(item, app),
+            };
+            app.notify(format!("Dry run, not sent to {}", self.to_string()));
+            app.dry_runs.push(format!("{}: {}", item.title, action));
+            return false;
+        }
+        let errors = app.errors.len();
         match self {
             Self::Cmd => cmd::download(item, app).await,
             Self::Qbit => qbit::download(item, app).await,
+            Self::Aria2 => aria2::download(item, app).await,
+            Self::Rest => rest::download(item, app).await,
         }
+        app.errors.len() == errors
     }
 
-    pub fn load_config(&self, app: &mut App) -> Result<(), Box<dyn Error>> {
+    pub async fn test_connection(&self, app: &App) -> Result<String, String> {
         match self {
+            Self::Cmd => cmd::test_connection(app),
+            Self::Rest => rest::test_connection(app),
+            Self::Qbit => {
+                let qbit = app.config.client.qbit.clone().unwrap_or_default();
+                qbit::test_connection(&qbit, Duration::from_secs(app.config.timeout)).await
+            }
+            Self::Aria2 => {
+                let aria2 = app.config.client.aria2.clone().unwrap_or_default();
+                aria2::test_connection(&aria2, Duration::from_secs(app.config.timeout)).await
+            }
+        }
+    }
+
+    // Ring the bell, show a desktop notification and run `download_notify_cmd` once a download,
+    // or a whole batch, is sent. All are off by default
+    pub fn notify_done(app: &mut App, title: &str) {
+        // Plenty of environments have no notification daemon, which isn't worth a popup. Spawned
+        // without waiting, so a slow daemon can't hold up the UI
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        if app.config.desktop_notify {
+            let _ = Command::new("notify-send")
+                .arg("nyaa")
+                .arg(format!("Added {} to {}", title, app.client.to_string()))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        }
+        if app.config.download_bell {
+            let mut out = stdout();
+            let _ = write!(out, "\x07").and_then(|_| out.flush());
+        }
+        let cmd_str = match app.config.download_notify_cmd.to_owned() {
+            Some(cmd_str) => cmd_str,
+            None => return,
+        };
+        #[cfg(target_os = "windows")]
+        let mut cmd = Command::new("powershell.exe");
+        #[cfg(target_os = "windows")]
+        cmd.arg("-Command");
+        #[cfg(not(target_os = "windows"))]
+        let mut cmd = Command::new("sh");
+        #[cfg(not(target_os = "windows"))]
+        cmd.arg("-c");
+
+        // Passed in the environment, so titles can't break out of the command
+        let res = cmd
+            .arg(&cmd_str)
+            .env("NYAA_TITLE", title)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Err(e) = res {
+            app.show_error(format!("{}:\nFailed to run:\n{}", cmd_str, e));
+        }
+    }
+
+    // A client's table is only added to the config file once it's selected
+    pub fn has_config(&self, config: &ClientConfig) -> bool {
+        match self {
+            Self::Cmd => config.cmd.is_some(),
+            Self::Qbit => config.qbit.is_some(),
+            Self::Aria2 => config.aria2.is_some(),
+            Self::Rest => config.rest.is_some(),
+        }
+    }
+
+    // Only fills in the client's settings in memory. This runs on every launch, so writing the
+    // config here would overwrite anything the user got wrong in it
+    pub fn load_config(&self, app: &mut App) -> Result<(), Box<dyn Error>> {
+        let res = match self {
             Self::Cmd => cmd::load_config(app),
             Self::Qbit => qbit::load_config(app),
+            Self::Aria2 => aria2::load_config(app),
+            Self::Rest => rest::load_config(app),
         };
-        app.config.default_client = self.to_owned();
-        app.config.clone().store()?;
-        Ok(())
+        app.config.client.default = self.to_owned();
+        res.map_err(|e| e.into())
     }
 }
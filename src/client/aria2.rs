@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    app::App,
+    source::{add_protocol, Item},
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Aria2Config {
+    pub rpc_url: String,
+    pub secret: Option<String>, // Value of --rpc-secret
+    pub use_magnet: Option<bool>,
+    pub dir: Option<String>, // Where aria2 saves downloads, instead of its own default
+}
+
+impl Default for Aria2Config {
+    fn default() -> Self {
+        Self {
+            rpc_url: "http://localhost:6800/jsonrpc".to_owned(),
+            secret: None,
+            use_magnet: None,
+            dir: None,
+        }
+    }
+}
+
+// Sends a single JSON-RPC call, returning its result or the error aria2 gave
+async fn call(
+    aria2: &Aria2Config,
+    method: &str,
+    mut params: Vec<Value>,
+    timeout: Duration,
+) -> Result<Value, String> {
+    if let Some(secret) = &aria2.secret {
+        params.insert(0, json!(format!("token:{}", secret)));
+    }
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": "nyaa",
+        "method": method,
+        "params": params,
+    });
+    let url = add_protocol(aria2.rpc_url.clone(), false);
+    let res = reqwest::Client::new()
+        .post(url.to_owned())
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send data to aria2 at {}:\n{}", url, e))?;
+    let status = res.status();
+    let text = res.text().await.unwrap_or_default();
+    let mut reply: Value = serde_json::from_str(&text).map_err(|_| {
+        format!(
+            "aria2 returned status code {}:\n{}",
+            status.as_u16(),
+            text.trim()
+        )
+    })?;
+    if let Some(err) = reply.get("error") {
+        return Err(format!(
+            "aria2 returned error {}:\n{}",
+            err["code"],
+            err["message"].as_str().unwrap_or_default()
+        ));
+    }
+    Ok(reply["result"].take())
+}
+
+async fn get_torrent(link: &str, timeout: Duration) -> Result<Vec<u8>, String> {
+    let res = reqwest::Client::new()
+        .get(link)
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {}:\n{}", link, e))?;
+    res.bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read {}:\n{}", link, e))
+}
+
+async fn add(aria2: &Aria2Config, item: &Item, timeout: Duration) -> Result<String, String> {
+    let mut options = serde_json::Map::new();
+    if let Some(dir) = &aria2.dir {
+        options.insert("dir".to_owned(), json!(dir));
+    }
+    let gid = match aria2.use_magnet {
+        None | Some(true) => {
+            let params = vec![json!([item.magnet_link]), json!(options)];
+            call(aria2, "aria2.addUri", params, timeout).await?
+        }
+        Some(false) => {
+            let torrent = get_torrent(&item.torrent_link, timeout).await?;
+            let params = vec![json!(STANDARD.encode(torrent)), json!([]), json!(options)];
+            call(aria2, "aria2.addTorrent", params, timeout).await?
+        }
+    };
+    Ok(gid.as_str().unwrap_or_default().to_owned())
+}
+
+// Ask for the version, without adding anything
+pub async fn test_connection(aria2: &Aria2Config, timeout: Duration) -> Result<String, String> {
+    let res = call(aria2, "aria2.getVersion", vec![], timeout).await?;
+    Ok(format!(
+        "Connected to aria2 {}",
+        res["version"].as_str().unwrap_or_default()
+    ))
+}
+
+pub fn load_config(app: &mut App) -> Result<(), String> {
+    if app.config.client.aria2.is_none() {
+        app.config.client.aria2 = Some(Aria2Config::default());
+    }
+    Ok(())
+}
+
+pub fn dry_run(item: &Item, app: &App) -> String {
+    let aria2 = app.config.client.aria2.clone().unwrap_or_default();
+    let url = add_protocol(aria2.rpc_url.clone(), false);
+    match aria2.use_magnet {
+        None | Some(true) => format!(
+            "would call aria2.addUri at {} with {}",
+            url, item.magnet_link
+        ),
+        Some(false) => format!(
+            "would call aria2.addTorrent at {} with the contents of {}",
+            url, item.torrent_link
+        ),
+    }
+}
+
+pub async fn download(item: &Item, app: &mut App) {
+    let aria2 = app.config.client.aria2.clone().unwrap_or_default();
+    let timeout = Duration::from_secs(app.config.timeout);
+    match add(&aria2, item, timeout).await {
+        Ok(gid) => app.notify(format!("Added to aria2 (gid {})", gid)),
+        Err(e) => app.show_error(format!("Failed to add \"{}\" to aria2:\n{}", item.title, e)),
+    }
+}
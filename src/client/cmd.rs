@@ -1,28 +1,124 @@
 use std::{
-    io::{BufReader, Read as _},
+    io::{BufReader, ErrorKind, Read as _},
     process::{Command, Stdio},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{app::App, source::Item};
 
-pub fn load_config(app: &mut App) {
-    if app.config.torrent_client_cmd.is_none() {
-        app.config.torrent_client_cmd = Some(
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CmdConfig {
+    pub command: String,
+    #[serde(default)]
+    pub open_with: Option<String>, // Program started directly instead of running `command`
+    #[serde(default)]
+    pub open_with_args: Vec<String>,
+}
+
+pub fn load_config(app: &mut App) -> Result<(), String> {
+    if app.config.client.cmd.is_none() {
+        app.config.client.cmd = Some(CmdConfig {
             #[cfg(windows)]
-            "curl \"{torrent}\" -o ~\\Downloads\\{file}".to_owned(),
+            command: "curl \"{torrent}\" -o ~\\Downloads\\{file}".to_owned(),
             #[cfg(unix)]
-            "curl \"{torrent}\" > ~/{file}".to_owned(),
-        );
+            command: "curl \"{torrent}\" > ~/{file}".to_owned(),
+            open_with: None,
+            open_with_args: vec![],
+        });
     }
+    Ok(())
 }
 
-pub async fn download(item: &Item, app: &mut App) {
-    let cmd = app.config.torrent_client_cmd.clone().unwrap_or_default();
-    let cmd_str = cmd
+// There's nothing to connect to, so just make sure there's a command to run
+pub fn test_connection(app: &App) -> Result<String, String> {
+    if let Some(program) = open_with(app) {
+        return Ok(format!("Downloads will open with {}", program));
+    }
+    match app.config.client.cmd.as_ref().map(|c| c.command.trim()) {
+        Some(cmd) if !cmd.is_empty() => Ok(format!("Downloads will run `{}`", cmd)),
+        _ => Err("No command is set in [client.cmd]".to_owned()),
+    }
+}
+
+fn fill(template: &str, item: &Item) -> String {
+    template
         .replace("{magnet}", &item.magnet_link)
         .replace("{torrent}", &item.torrent_link)
         .replace("{title}", &item.title)
-        .replace("{file}", &item.file_name);
+        .replace("{file}", &item.file_name)
+}
+
+fn command(item: &Item, app: &App) -> String {
+    let cmd = app
+        .config
+        .client
+        .cmd
+        .clone()
+        .map(|c| c.command)
+        .unwrap_or_default();
+    fill(&cmd, item)
+}
+
+fn open_with(app: &App) -> Option<String> {
+    app.config
+        .client
+        .cmd
+        .as_ref()
+        .and_then(|c| c.open_with.to_owned())
+        .filter(|p| !p.trim().is_empty())
+}
+
+// Arguments for `open_with`, which is just the magnet link unless set
+fn open_with_args(item: &Item, app: &App) -> Vec<String> {
+    let args = app
+        .config
+        .client
+        .cmd
+        .as_ref()
+        .map(|c| c.open_with_args.to_owned())
+        .unwrap_or_default();
+    match args.is_empty() {
+        true => vec![item.magnet_link.to_owned()],
+        false => args.iter().map(|a| fill(a, item)).collect(),
+    }
+}
+
+pub fn dry_run(item: &Item, app: &App) -> String {
+    match open_with(app) {
+        Some(program) => format!(
+            "would start {} with {}",
+            program,
+            open_with_args(item, app).join(" ")
+        ),
+        None => format!("would run `{}`", command(item, app)),
+    }
+}
+
+// Start the program without waiting for it, since it's usually a GUI that stays open
+fn open(program: &str, item: &Item, app: &mut App) {
+    let res = Command::new(program)
+        .args(open_with_args(item, app))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match res {
+        Ok(_) => app.notify(format!("Opened in {}", program)),
+        Err(e) if e.kind() == ErrorKind::NotFound => app.show_error(format!(
+            "{} was not found.\nCheck that open_with in [client.cmd] is the full path to the program",
+            program
+        )),
+        Err(e) => app.show_error(format!("Failed to start {}:\n{}", program, e)),
+    }
+}
+
+pub async fn download(item: &Item, app: &mut App) {
+    if let Some(program) = open_with(app) {
+        open(&program, item, app);
+        return;
+    }
+    let cmd_str = command(item, app);
 
     // Use platform-specific shell to launch command
     #[cfg(target_os = "windows")]
@@ -65,5 +161,7 @@ pub async fn download(item: &Item, app: &mut App) {
             "{}:\nExited with status code {}:\n{}",
             cmd_str, output.status, err_str
         ));
+    } else {
+        app.notify("Ran download command");
     }
 }
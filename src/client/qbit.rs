@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, env, fs, time::Duration};
 
 use reqwest::{
     header::{COOKIE, REFERER, SET_COOKIE},
@@ -17,6 +17,8 @@ pub struct QbitConfig {
     pub base_url: String,
     pub username: String,
     pub password: String,
+    pub password_file: Option<String>, // Read the password from here instead
+    pub password_env: Option<String>,  // Or from this environment variable
     pub use_magnet: Option<bool>,
     pub savepath: Option<String>,
     pub category: Option<String>, // Single category
@@ -41,6 +43,30 @@ fn bool_str(b: Option<bool>) -> Option<String> {
 }
 
 impl QbitConfig {
+    fn password(&self) -> Result<String, String> {
+        if let Some(var) = &self.password_env {
+            return env::var(var).map_err(|e| {
+                format!(
+                    "Failed to read qBittorrent password_env \"{}\":\n{}",
+                    var, e
+                )
+            });
+        }
+        match &self.password_file {
+            Some(path) => fs::read_to_string(path)
+                .map(|p| p.trim_end_matches(['\r', '\n']).to_owned())
+                .map_err(|e| format!("Failed to read password_file \"{}\":\n{}", path, e)),
+            None => Ok(self.password.to_owned()),
+        }
+    }
+
+    fn link(&self, item: &Item) -> String {
+        match self.use_magnet {
+            None | Some(true) => item.magnet_link.to_owned(),
+            Some(false) => item.torrent_link.to_owned(),
+        }
+    }
+
     fn to_form(&self, url: String) -> QbitForm {
         QbitForm {
             urls: url,
@@ -67,6 +93,8 @@ impl Default for QbitConfig {
             base_url: "http://localhost:8080".to_owned(),
             username: "admin".to_owned(),
             password: "adminadmin".to_owned(),
+            password_file: None,
+            password_env: None,
             use_magnet: None,
             savepath: None,
             category: None,
@@ -126,7 +154,7 @@ async fn login(qbit: &QbitConfig, timeout: Duration) -> Result<String, String> {
     let url = format!("{}/api/v2/auth/login", base_url);
     let mut params = HashMap::new();
     params.insert("username", qbit.username.to_owned());
-    params.insert("password", qbit.password.to_owned());
+    params.insert("password", qbit.password()?);
     let res = client.post(url).form(&params).timeout(timeout).send().await;
     let res = res.map_err(|e| format!("Failed to send data to qBittorrent\n{}", e))?;
     let headers = res.headers().clone();
@@ -181,14 +209,54 @@ async fn add_torrent(
         .await
 }
 
-pub fn load_config(app: &mut App) {
-    if app.config.qbit.is_none() {
-        app.config.qbit = Some(QbitConfig::default());
+// Log in and ask for the version, without adding anything
+pub async fn test_connection(qbit: &QbitConfig, timeout: Duration) -> Result<String, String> {
+    let base_url = add_protocol(qbit.base_url.clone(), false);
+    let sid = login(qbit, timeout)
+        .await
+        .map_err(|e| format!("Failed to log in to qBittorrent at {}:\n{}", base_url, e))?;
+    let res = reqwest::Client::new()
+        .get(format!("{}/api/v2/app/version", base_url))
+        .header(REFERER, base_url.to_owned())
+        .header(COOKIE, sid.to_owned())
+        .timeout(timeout)
+        .send()
+        .await;
+    logout(qbit, sid, timeout).await;
+
+    let res = res.map_err(|e| format!("Failed to get version from qBittorrent:\n{}", e))?;
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    if status != StatusCode::OK {
+        return Err(format!(
+            "qBittorrent returned status code {}:\n{}",
+            status.as_u16(),
+            body.trim()
+        ));
+    }
+    Ok(format!("Connected to qBittorrent {}", body.trim()))
+}
+
+// Fails if the password can't be found, so it's reported before anything is downloaded
+pub fn load_config(app: &mut App) -> Result<(), String> {
+    if app.config.client.qbit.is_none() {
+        app.config.client.qbit = Some(QbitConfig::default());
     }
+    let qbit = app.config.client.qbit.clone().unwrap_or_default();
+    qbit.password().map(|_| ())
+}
+
+pub fn dry_run(item: &Item, app: &App) -> String {
+    let qbit = app.config.client.qbit.clone().unwrap_or_default();
+    format!(
+        "would add {} to qBittorrent at {}",
+        qbit.link(item),
+        add_protocol(qbit.base_url.clone(), false)
+    )
 }
 
 pub async fn download(item: &Item, app: &mut App) {
-    let qbit = app.config.qbit.clone().unwrap();
+    let qbit = app.config.client.qbit.clone().unwrap_or_default();
     let timeout = Duration::from_secs(app.config.timeout);
     let sid = match login(&qbit, timeout).await {
         Ok(s) => s,
@@ -197,20 +265,29 @@ pub async fn download(item: &Item, app: &mut App) {
             return;
         }
     };
-    let link = match qbit.use_magnet {
-        None | Some(true) => item.magnet_link.to_owned(),
-        Some(false) => item.torrent_link.to_owned(),
-    };
-    let Ok(res) = add_torrent(&qbit, sid.to_owned(), link, timeout).await else {
-        app.show_error("Failed to get response");
-        return;
+    let res = match add_torrent(&qbit, sid.to_owned(), qbit.link(item), timeout).await {
+        Ok(res) => res,
+        Err(e) => {
+            app.show_error(format!("Failed to send torrent to qBittorrent:\n{}", e));
+            return;
+        }
     };
-    if res.status() != StatusCode::OK {
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    // qBittorrent replies "Ok." or "Fails." even when the status is 200
+    if status != StatusCode::OK || body.trim() == "Fails." {
         app.show_error(format!(
-            "qBittorrent returned status code {}",
-            res.status().as_u16()
+            "qBittorrent failed to add \"{}\" (status code {}):\n{}",
+            item.title,
+            status.as_u16(),
+            body.trim()
         ));
-        return;
+    } else {
+        let hash = match item.info_hash.is_empty() {
+            true => "".to_owned(),
+            false => format!(" ({})", item.info_hash),
+        };
+        app.notify(format!("Added to qBittorrent{}", hash));
     }
 
     logout(&qbit, sid.clone(), timeout).await;
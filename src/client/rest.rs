@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use regex::Regex;
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Method, Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::App,
+    source::{add_protocol, Item},
+};
+
+static PLACEHOLDERS: &[&str] = &["magnet", "torrent", "title", "file"];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RestConfig {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self {
+            method: "POST".to_owned(),
+            url: "http://localhost:8080/add".to_owned(),
+            headers: BTreeMap::from([("Content-Type".to_owned(), "application/json".to_owned())]),
+            body: Some("{\"url\": \"{magnet}\"}".to_owned()),
+        }
+    }
+}
+
+fn fill(template: &str, item: &Item, escape: fn(&str) -> String) -> String {
+    template
+        .replace("{magnet}", &escape(&item.magnet_link))
+        .replace("{torrent}", &escape(&item.torrent_link))
+        .replace("{title}", &escape(&item.title))
+        .replace("{file}", &escape(&item.file_name))
+}
+
+fn raw(value: &str) -> String {
+    value.to_owned()
+}
+
+// Titles can contain quotes and backslashes, which would break a JSON body
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted.trim_matches('"').to_owned()
+}
+
+impl RestConfig {
+    fn fill_body(&self, item: &Item) -> Option<String> {
+        let json = self.headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("content-type") && value.to_lowercase().contains("json")
+        });
+        let escape: fn(&str) -> String = match json {
+            true => json_escape,
+            false => raw,
+        };
+        self.body.as_ref().map(|b| fill(b, item, escape))
+    }
+
+    // Catch mistakes when the config is loaded, rather than when something is downloaded
+    fn validate(&self) -> Result<(), String> {
+        Method::from_str(&self.method.to_uppercase())
+            .map_err(|_| format!("Invalid method \"{}\" in [client.rest]", self.method))?;
+        let placeholder = Regex::new(r"\{(\w+)\}").map_err(|e| e.to_string())?;
+        let templates = [&self.url]
+            .into_iter()
+            .chain(self.headers.values())
+            .chain(self.body.iter());
+        for template in templates {
+            if let Some(unknown) = placeholder
+                .captures_iter(template)
+                .map(|c| c[1].to_owned())
+                .find(|name| !PLACEHOLDERS.contains(&name.as_str()))
+            {
+                return Err(format!(
+                    "Unknown placeholder {{{}}} in [client.rest]. Use {}",
+                    unknown,
+                    PLACEHOLDERS
+                        .iter()
+                        .map(|p| format!("{{{}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+        let url = add_protocol(placeholder.replace_all(&self.url, "x").to_string(), false);
+        Url::parse(&url)
+            .map_err(|e| format!("Invalid url \"{}\" in [client.rest]:\n{}", url, e))?;
+        for (name, value) in self.headers.iter() {
+            HeaderName::from_str(name)
+                .map_err(|_| format!("Invalid header name \"{}\" in [client.rest]", name))?;
+            HeaderValue::from_str(value)
+                .map_err(|_| format!("Invalid value for header \"{}\" in [client.rest]", name))?;
+        }
+        Ok(())
+    }
+}
+
+// There's no standard way to ask an arbitrary client if it's up, so only check the config
+pub fn test_connection(app: &App) -> Result<String, String> {
+    let rest = app.config.client.rest.clone().unwrap_or_default();
+    rest.validate()?;
+    Ok(format!(
+        "Downloads will send {} {}",
+        rest.method.to_uppercase(),
+        rest.url
+    ))
+}
+
+pub fn load_config(app: &mut App) -> Result<(), String> {
+    if app.config.client.rest.is_none() {
+        app.config.client.rest = Some(RestConfig::default());
+    }
+    app.config
+        .client
+        .rest
+        .clone()
+        .unwrap_or_default()
+        .validate()
+}
+
+pub fn dry_run(item: &Item, app: &App) -> String {
+    let rest = app.config.client.rest.clone().unwrap_or_default();
+    let headers = rest
+        .headers
+        .iter()
+        .map(|(name, value)| format!("\n{}: {}", name, fill(value, item, raw)))
+        .collect::<String>();
+    let body = rest
+        .fill_body(item)
+        .map(|b| format!("\n\n{}", b))
+        .unwrap_or_default();
+    format!(
+        "would send {} {}{}{}",
+        rest.method.to_uppercase(),
+        add_protocol(fill(&rest.url, item, raw), false),
+        headers,
+        body
+    )
+}
+
+pub async fn download(item: &Item, app: &mut App) {
+    let rest = app.config.client.rest.clone().unwrap_or_default();
+    if let Err(e) = rest.validate() {
+        app.show_error(e);
+        return;
+    }
+    let method = Method::from_str(&rest.method.to_uppercase()).unwrap_or(Method::POST);
+    let url = add_protocol(fill(&rest.url, item, raw), false);
+    let mut req = reqwest::Client::new()
+        .request(method, url.to_owned())
+        .timeout(Duration::from_secs(app.config.timeout));
+    for (name, value) in rest.headers.iter() {
+        // Checked here too, since a placeholder can fill in characters headers don't allow
+        let value = match HeaderValue::from_str(&fill(value, item, raw)) {
+            Ok(value) => value,
+            Err(_) => {
+                app.show_error(format!(
+                    "Header \"{}\" in [client.rest] can't be sent for \"{}\":\nHeader values can only contain ASCII, so use {{title}} in the url or body instead",
+                    name, item.title
+                ));
+                return;
+            }
+        };
+        req = req.header(name, value);
+    }
+    if let Some(body) = rest.fill_body(item) {
+        req = req.body(body);
+    }
+    let res = match req.send().await {
+        Ok(res) => res,
+        Err(e) => {
+            app.show_error(format!(
+                "Failed to send \"{}\" to {}:\n{}",
+                item.title, url, e
+            ));
+            return;
+        }
+    };
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        app.show_error(format!(
+            "{} returned status code {} for \"{}\":\n{}",
+            url,
+            status.as_u16(),
+            item.title,
+            body.trim()
+        ));
+        return;
+    }
+    // The url could contain a whole magnet link, so only show where it went
+    let host = Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_owned()))
+        .unwrap_or(url);
+    app.notify(format!("Sent to {}", host));
+}
@@ -0,0 +1,72 @@
+use std::{
+    error::Error,
+    io::{stdout, BufReader, Read as _, Write as _},
+    process::{Command, Stdio},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::app::App;
+
+// Many terminals drop OSC 52 sequences past around this size, without telling anyone
+static OSC52_LIMIT: usize = 100_000;
+
+fn run_cmd(cmd_str: &str, text: &str) -> Result<(), Box<dyn Error>> {
+    // Use platform-specific shell to launch command
+    #[cfg(target_os = "windows")]
+    let mut cmd = Command::new("powershell.exe");
+    #[cfg(target_os = "windows")]
+    cmd.arg("-Command");
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = Command::new("sh");
+    #[cfg(not(target_os = "windows"))]
+    cmd.arg("-c");
+
+    let mut child = cmd
+        .arg(cmd_str)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if output.status.code() != Some(0) {
+        let mut err = BufReader::new(&*output.stderr);
+        let mut err_str = String::new();
+        err.read_to_string(&mut err_str).unwrap_or(0);
+        return Err(format!("Exited with status code {}:\n{}", output.status, err_str).into());
+    }
+    Ok(())
+}
+
+// Ask the terminal to set the clipboard, which also works over ssh. Terminals without OSC 52
+// support silently ignore it, so there's no way to tell if it worked
+fn osc52(text: &str) -> Result<(), Box<dyn Error>> {
+    let encoded = STANDARD.encode(text);
+    if encoded.len() > OSC52_LIMIT {
+        return Err(format!(
+            "{} bytes is too much for most terminals to copy.\nSet clipboard_cmd in the config to copy it instead",
+            text.len()
+        )
+        .into());
+    }
+    let mut out = stdout();
+    write!(out, "\x1b]52;c;{}\x07", encoded)?;
+    out.flush()?;
+    Ok(())
+}
+
+// Copy text with `clipboard_cmd`, or OSC 52 if it isn't set. `what` describes the text for the
+// confirmation message
+pub fn copy(app: &mut App, text: &str, what: &str) {
+    let res = match app.config.clipboard_cmd.to_owned() {
+        Some(cmd) => run_cmd(&cmd, text).map_err(|e| format!("{}:\n{}", cmd, e)),
+        None => osc52(text).map_err(|e| e.to_string()),
+    };
+    match res {
+        Ok(()) => app.notify(format!("Copied {} to clipboard", what)),
+        Err(e) => app.show_error(format!("Failed to copy {} to clipboard:\n{}", what, e)),
+    }
+}
@@ -1,73 +1,606 @@
+use std::{collections::BTreeMap, env, error::Error, fs, path::PathBuf};
+
 use crate::{
-    app::{App, Widgets, APP_NAME},
-    client::{qbit::QbitConfig, Client},
-    source::Sources,
+    app::{App, StartMode, Widgets, APP_NAME},
+    client::ClientConfig,
+    source::{add_protocol, Sources},
     widget::{
         category::{self, ALL_CATEGORIES},
         filter::Filter,
-        sort::Sort,
-        theme::{self, THEMES},
+        results::{ColumnsConfig, EnterAction},
+        search::EmptySearch,
+        sort::{self, Sort},
+        theme::{self, Border, THEMES},
+        EnumIter,
     },
 };
-use confy::ConfyError;
+use directories::ProjectDirs;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use toml_edit::{DocumentMut, Item};
 
 pub static CONFIG_FILE: &str = "config";
+pub static CONFIG_DIR_ENV: &str = "NYAA_CONFIG_DIR";
+pub static CACHE_DIR_ENV: &str = "NYAA_CACHE_DIR";
+pub static CONFIG_VERSION: u32 = 2;
+
+fn project_dirs() -> Result<ProjectDirs, Box<dyn Error>> {
+    ProjectDirs::from("rs", "", APP_NAME).ok_or("Failed to find home directory".into())
+}
+
+// `$XDG_CONFIG_HOME/nyaa` on linux, unless overridden with `NYAA_CONFIG_DIR`
+pub fn config_dir() -> Result<PathBuf, Box<dyn Error>> {
+    match env::var_os(CONFIG_DIR_ENV) {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(project_dirs()?.config_dir().to_owned()),
+    }
+}
+
+// `$XDG_CACHE_HOME/nyaa` on linux, unless overridden with `NYAA_CACHE_DIR`
+pub fn cache_dir() -> Result<PathBuf, Box<dyn Error>> {
+    match env::var_os(CACHE_DIR_ENV) {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(project_dirs()?.cache_dir().to_owned()),
+    }
+}
+
+fn config_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config_dir()?.join(format!("{}.toml", CONFIG_FILE)))
+}
+
+// Upgrade a config table from `version` to `CONFIG_VERSION`, one version at a time
+fn migrate(table: &mut toml::Table, version: i64) {
+    for v in version..CONFIG_VERSION as i64 {
+        if v == 0 {
+            // base_url used to default to "https://nyaa.si/", which doubled up slashes
+            if let Some(toml::Value::String(url)) = table.get_mut("base_url") {
+                *url = url.trim_end_matches('/').to_owned();
+            }
+        }
+        if v == 1 {
+            // Client settings moved into a single [client] table
+            let mut client = toml::Table::new();
+            if let Some(default) = table.remove("default_client") {
+                client.insert("default".to_owned(), default);
+            }
+            if let Some(cmd) = table.remove("torrent_client_cmd") {
+                let cmd = toml::Table::from_iter([("command".to_owned(), cmd)]);
+                client.insert("cmd".to_owned(), toml::Value::Table(cmd));
+            }
+            if let Some(qbit) = table.remove("qBittorrent") {
+                client.insert("qBittorrent".to_owned(), qbit);
+            }
+            if !client.is_empty() {
+                table.insert("client".to_owned(), toml::Value::Table(client));
+            }
+        }
+    }
+    table.insert(
+        "version".to_owned(),
+        toml::Value::Integer(CONFIG_VERSION as i64),
+    );
+}
+
+// The same trackers nyaa puts in its own magnet links
+static TRACKERS: &[&str] = &[
+    "http://nyaa.tracker.wf:7777/announce",
+    "udp://open.stealth.si:80/announce",
+    "udp://tracker.opentrackr.org:1337/announce",
+    "udp://exodus.desync.com:6969/announce",
+    "udp://tracker.torrent.eu.org:451/announce",
+];
+
+// Comments written above each field when storing the config
+static COMMENTS: &[(&str, &str)] = &[
+    (
+        "version",
+        "Version of the config format, used to upgrade older configs",
+    ),
+    (
+        "default_category",
+        "Possible values are listed in CATEGORIES.md",
+    ),
+    (
+        "default_filter",
+        "NoFilter, NoRemakes, TrustedOnly or Batches",
+    ),
+    (
+        "default_sort",
+        "Date, Downloads, Seeders, Leechers, Size or Ratio",
+    ),
+    (
+        "sorts",
+        "Sorts listed in the sort popup, in order, out of Date, Downloads, Seeders, Leechers, Size and Ratio",
+    ),
+    (
+        "default_theme",
+        "Default, Dracula, Gruvbox, Catppuccin Macchiato or Colorblind",
+    ),
+    ("default_search", "Search entered when the app is opened"),
+    (
+        "resume_search",
+        "Open the search and page from when the app was last closed, instead of default_search",
+    ),
+    (
+        "start_mode",
+        "results or search, which is focused when the app is opened",
+    ),
+    (
+        "enter_action",
+        "download, copy_magnet or open_browser, what Enter does with a result",
+    ),
+    (
+        "empty_search",
+        "All, Category or Block. What an empty search does, since it lists every torrent",
+    ),
+    ("default_source", "NyaaHtml or NyaaRss"),
+    (
+        "date_format",
+        "Format of the Date column, see chrono's strftime docs",
+    ),
+    ("base_url", "Url of nyaa.si or one of its mirrors"),
+    ("timeout", "Seconds to wait for a request before giving up"),
+    ("confirm_quit", "Ask before exiting the app"),
+    (
+        "cache_results",
+        "Cache results on disk so repeated searches are instant",
+    ),
+    (
+        "cache_ttl",
+        "Seconds before cached results are fetched again",
+    ),
+    (
+        "live_search",
+        "Search while typing, once input settles. Sends more requests",
+    ),
+    (
+        "live_search_delay",
+        "Milliseconds to wait after typing before a live search",
+    ),
+    (
+        "search_count",
+        "Show how many results there are while typing, without loading them. Sends more requests",
+    ),
+    (
+        "auto_refresh",
+        "Seconds between refreshing the current results, 0 to disable",
+    ),
+    (
+        "min_seeders",
+        "Hide results with fewer seeders than this, toggled with z",
+    ),
+    (
+        "dedup",
+        "Merge results that are reposts of the same torrent",
+    ),
+    (
+        "download_delay",
+        "Milliseconds to wait between downloads when downloading many at once",
+    ),
+    (
+        "download_bell",
+        "Ring the terminal bell once a download or batch is sent",
+    ),
+    (
+        "desktop_notify",
+        "Show a desktop notification with notify-send once a download or batch is sent",
+    ),
+    (
+        "download_notify_cmd",
+        "Command run once a download or batch is sent, with its title in $NYAA_TITLE",
+    ),
+    (
+        "status_bar",
+        "Show the source, client, page and request status at the bottom",
+    ),
+    (
+        "border",
+        "Plain, Rounded, Double, Thick or None. Unset uses the theme's border",
+    ),
+    (
+        "results_border",
+        "Draw a full border around the results, or only a line above them",
+    ),
+    (
+        "search_border",
+        "Draw a full border around the search bar, or only a line above it",
+    ),
+    (
+        "key_legend",
+        "Show a few of the most useful keys for the current mode at the bottom",
+    ),
+    (
+        "dense",
+        "Fit as many results as possible on small screens, with a one line search bar",
+    ),
+    (
+        "wrap_titles",
+        "Wrap long titles onto multiple lines, toggled with w",
+    ),
+    (
+        "max_title_width",
+        "Most columns the title can take up, 0 for as many as are left over",
+    ),
+    (
+        "row_spacing",
+        "Leave a blank line between results, toggled with e",
+    ),
+    (
+        "normalize_sizes",
+        "Show sizes computed from bytes instead of as the site formats them",
+    ),
+    (
+        "dim_remakes",
+        "Dim and strike through remakes instead of showing them normally, toggled with R",
+    ),
+    (
+        "mark_seen",
+        "Dim and italicize torrents that were downloaded or opened before",
+    ),
+    (
+        "recent_hours",
+        "Highlight the dates of torrents posted within this many hours, 0 to disable",
+    ),
+    (
+        "status_symbols",
+        "Put ✓ before trusted titles and ⚠ before remakes",
+    ),
+    (
+        "prefer_remake_color",
+        "Color trusted remakes as remakes instead of as trusted",
+    ),
+    (
+        "wrap_navigation",
+        "Moving past the end of a list goes back to the start, and the other way around",
+    ),
+    (
+        "mouse",
+        "Use the mouse, e.g. click a column header to sort by it",
+    ),
+    (
+        "clipboard_cmd",
+        "Command that reads text to copy from stdin. Unset uses the terminal",
+    ),
+    (
+        "debug",
+        "Press F12 on a result to see everything parsed for it",
+    ),
+    (
+        "exclude",
+        "Terms left out of every search, e.g. [\"HEVC\"], toggled with x",
+    ),
+    (
+        "trackers",
+        "Trackers added to magnet links that don't already have them",
+    ),
+    (
+        "aliases",
+        "Shortcuts for searches, e.g. frieren = \"Frieren 1080p\" is searched by typing !frieren",
+    ),
+    (
+        "columns",
+        "Columns shown in the results table. Name is always shown",
+    ),
+    (
+        "client",
+        "Download client, cmd, qBittorrent, aria2 or rest, followed by settings for each",
+    ),
+];
+
+fn join_errors(errors: Vec<String>) -> Option<String> {
+    match errors.is_empty() {
+        true => None,
+        false => Some(errors.join("\n\n")),
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Config {
-    pub torrent_client_cmd: Option<String>,
+    pub version: u32,
     pub default_category: String,
     pub default_filter: Filter,
     pub default_sort: Sort,
+    pub sorts: Vec<String>,
     pub default_theme: String,
     pub default_search: String,
+    pub resume_search: bool,
+    pub empty_search: EmptySearch,
+    pub start_mode: StartMode,
+    pub enter_action: EnterAction,
     pub default_source: Sources,
-    pub default_client: Client,
     pub date_format: String,
     pub base_url: String,
     pub timeout: u64,
-
-    #[serde(rename = "qBittorrent")]
-    pub qbit: Option<QbitConfig>,
+    pub confirm_quit: bool,
+    pub cache_results: bool,
+    pub cache_ttl: u64,
+    pub live_search: bool,
+    pub live_search_delay: u64,
+    pub search_count: bool,
+    pub auto_refresh: u64,
+    pub min_seeders: u32,
+    pub dedup: bool,
+    pub download_delay: u64,
+    pub download_bell: bool,
+    pub desktop_notify: bool,
+    pub download_notify_cmd: Option<String>,
+    pub status_bar: bool,
+    pub border: Option<Border>,
+    pub results_border: bool,
+    pub search_border: bool,
+    pub key_legend: bool,
+    pub dense: bool,
+    pub wrap_titles: bool,
+    pub max_title_width: u16,
+    pub row_spacing: bool,
+    pub normalize_sizes: bool,
+    pub dim_remakes: bool,
+    pub mark_seen: bool,
+    pub recent_hours: u64,
+    pub status_symbols: bool,
+    pub prefer_remake_color: bool,
+    pub wrap_navigation: bool,
+    pub mouse: bool,
+    pub clipboard_cmd: Option<String>,
+    pub debug: bool,
+    pub exclude: Vec<String>,
+    pub trackers: Vec<String>,
+    pub aliases: BTreeMap<String, String>,
+    pub columns: ColumnsConfig,
+    pub client: ClientConfig,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
-            torrent_client_cmd: None,
+            version: CONFIG_VERSION,
             default_category: ALL_CATEGORIES[0].entries[0].cfg.to_owned(),
             default_filter: Filter::NoFilter,
             default_sort: Sort::Date,
+            sorts: Sort::iter().map(|s| s.to_string()).collect(),
             default_source: Sources::NyaaHtml,
-            default_client: Client::Cmd,
             default_theme: THEMES[0].name.to_owned(),
             default_search: "".to_owned(),
+            resume_search: false,
+            empty_search: EmptySearch::All,
+            start_mode: StartMode::Results,
+            enter_action: EnterAction::Download,
             date_format: "%Y-%m-%d %H:%M".to_owned(),
-            base_url: "https://nyaa.si/".to_owned(),
+            base_url: "https://nyaa.si".to_owned(),
             timeout: 30,
-            qbit: None,
+            confirm_quit: false,
+            cache_results: false,
+            cache_ttl: 600,
+            live_search: false,
+            live_search_delay: 300,
+            search_count: false,
+            auto_refresh: 0,
+            min_seeders: 0,
+            dedup: false,
+            download_delay: 1000,
+            download_bell: false,
+            desktop_notify: false,
+            download_notify_cmd: None,
+            status_bar: true,
+            border: None,
+            results_border: true,
+            search_border: true,
+            key_legend: false,
+            dense: false,
+            wrap_titles: false,
+            max_title_width: 0,
+            row_spacing: false,
+            normalize_sizes: false,
+            dim_remakes: false,
+            mark_seen: true,
+            recent_hours: 0,
+            status_symbols: false,
+            prefer_remake_color: false,
+            wrap_navigation: true,
+            mouse: false,
+            clipboard_cmd: None,
+            debug: false,
+            exclude: vec![],
+            trackers: TRACKERS.iter().map(|t| t.to_string()).collect(),
+            aliases: BTreeMap::new(),
+            columns: ColumnsConfig::default(),
+            client: ClientConfig::default(),
         }
     }
 }
 
 impl Config {
-    pub fn load() -> Result<Config, ConfyError> {
-        confy::load::<Config>(APP_NAME, CONFIG_FILE)
+    // Returns the config along with an error describing any fields that were skipped
+    pub fn load() -> Result<(Config, Option<String>), Box<dyn Error>> {
+        let path = config_path()?;
+        if !path.exists() {
+            // Write a documented starting point on first run
+            let config = Config::default();
+            let err = config.clone().store().err().map(|e| {
+                format!(
+                    "Failed to write default config to {}:\n{}",
+                    path.display(),
+                    e
+                )
+            });
+            return Ok((config, err));
+        }
+        let mut content = fs::read_to_string(&path)?;
+        let mut table = content
+            .parse::<toml::Table>()
+            .map_err(|e| format!("Failed to parse {}:\n{}", path.display(), e))?;
+        let mut errors = vec![];
+
+        let version = table
+            .get("version")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(0);
+        if version < CONFIG_VERSION as i64 {
+            migrate(&mut table, version);
+            content = toml::to_string(&table)?;
+            // Rewriting loses comments and layout, so keep the original next to it
+            let backup = path.with_extension("toml.bak");
+            let res = fs::copy(&path, &backup).and_then(|_| fs::write(&path, &content));
+            errors.push(match res {
+                Ok(()) => format!(
+                    "Updated {} to config version {}.\n{} {}",
+                    path.display(),
+                    CONFIG_VERSION,
+                    "Comments aren't kept, so the old file was backed up to",
+                    backup.display()
+                ),
+                Err(e) => format!(
+                    "Failed to write migrated config to {}:\n{}",
+                    path.display(),
+                    e
+                ),
+            });
+        }
+
+        match toml::from_str::<Config>(&content) {
+            Ok(config) => return Ok((config, join_errors(errors))),
+            Err(e) => errors.push(format!("Failed to parse {}:\n{}", path.display(), e)),
+        };
+
+        // Keep every top-level field that parses on its own
+        let mut valid = toml::Table::new();
+        let mut skipped = vec![];
+        for (key, value) in table {
+            let field = toml::Table::from_iter([(key.to_owned(), value.to_owned())]);
+            match toml::Value::Table(field).try_into::<Config>() {
+                Ok(_) => {
+                    valid.insert(key, value);
+                }
+                Err(_) => skipped.push(key),
+            }
+        }
+        // Fields can be fine on their own but not together, leaving only the parse error above
+        if !skipped.is_empty() {
+            errors.push(format!(
+                "Skipped invalid fields: {}\nAll other fields were loaded.",
+                skipped.join(", ")
+            ));
+        }
+        let config = toml::Value::Table(valid).try_into::<Config>()?;
+        Ok((config, join_errors(errors)))
     }
-    pub fn store(self) -> Result<(), ConfyError> {
-        confy::store::<Config>(APP_NAME, CONFIG_FILE, self)
+    pub fn store(self) -> Result<(), Box<dyn Error>> {
+        let path = config_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_commented_toml()?)?;
+        Ok(())
+    }
+    // Write only `keys` into the config file, like "default_theme" or "columns.size". Anything
+    // else in it is left alone, including comments and fields that were skipped as invalid
+    pub fn store_keys<S: AsRef<str>>(&self, keys: &[S]) -> Result<(), Box<dyn Error>> {
+        let path = config_path()?;
+        if !path.exists() {
+            return self.clone().store();
+        }
+        let mut doc = fs::read_to_string(&path)?.parse::<DocumentMut>()?;
+        let new = toml::to_string(self)?.parse::<DocumentMut>()?;
+        for key in keys {
+            let key = key.as_ref();
+            let parts: Vec<&str> = key.split('.').collect();
+            let value = parts
+                .iter()
+                .try_fold(new.as_item(), |item, part| item.get(part));
+            let (last, parents) = parts.split_last().ok_or("Empty config key")?;
+            let mut table = doc.as_table_mut();
+            for part in parents {
+                table = table
+                    .entry(part)
+                    .or_insert(toml_edit::table())
+                    .as_table_mut()
+                    .ok_or(format!("\"{}\" in config is not a table", part))?;
+            }
+            match (table.get_mut(last), value) {
+                // Keep the comments around values that are already there
+                (Some(Item::Value(old)), Some(Item::Value(value))) => {
+                    let decor = old.decor().to_owned();
+                    *old = value.to_owned();
+                    *old.decor_mut() = decor;
+                }
+                (_, Some(value)) => {
+                    table.insert(last, value.to_owned());
+                }
+                (_, None) => {
+                    table.remove(last);
+                }
+            }
+        }
+        fs::write(path, doc.to_string())?;
+        Ok(())
+    }
+    fn to_commented_toml(&self) -> Result<String, Box<dyn Error>> {
+        let value = toml::Value::try_from(self)?;
+        let table = value.as_table().ok_or("Config is not a table")?;
+        // Tables must come after all plain values
+        let (tables, values): (Vec<_>, Vec<_>) = table.iter().partition(|(_, v)| v.is_table());
+        let mut out = String::new();
+        for (key, value) in values.into_iter().chain(tables) {
+            if let Some((_, comment)) = COMMENTS.iter().find(|(k, _)| *k == key.as_str()) {
+                out.push_str(&format!("# {}\n", comment));
+            }
+            let field = toml::Table::from_iter([(key.to_owned(), value.to_owned())]);
+            out.push_str(&toml::to_string(&field)?);
+        }
+        Ok(out)
     }
     pub fn apply(&self, app: &mut App, w: &mut Widgets) {
         app.config = self.to_owned();
+        if let Err(e) = Url::parse(&add_protocol(app.config.base_url.clone(), true)) {
+            let default = Config::default().base_url;
+            app.show_error(format!(
+                "Invalid base_url \"{}\" in config:\n{}\nUsing \"{}\" instead",
+                app.config.base_url, e, default
+            ));
+            app.config.base_url = default;
+        }
+        // Avoid double slashes when building urls
+        app.config.base_url = app.config.base_url.trim_end_matches('/').to_owned();
         w.search.input.input = app.config.default_search.to_owned();
         w.search.input.cursor = w.search.input.input.len();
         w.sort.selected = app.config.default_sort.to_owned();
+        // Names are checked one at a time, so a typo doesn't lose the whole list
+        let mut sorts: Vec<Sort> = vec![];
+        let mut unknown = vec![];
+        for name in app.config.sorts.iter() {
+            match sort::find_sort(name) {
+                Some(s) if !sorts.contains(&s) => sorts.push(s),
+                Some(_) => {}
+                None => unknown.push(format!("\"{}\"", name)),
+            }
+        }
+        if !unknown.is_empty() {
+            app.show_error(format!(
+                "Unknown sorts {} in config.\nUse {}",
+                unknown.join(", "),
+                Sort::iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        w.sort.with_sorts(&sorts);
         w.filter.selected = app.config.default_filter.to_owned();
-        app.client = app.config.default_client.to_owned();
+        w.results.min_seeders = app.config.min_seeders;
+        w.results.dedup = app.config.dedup;
+        // Dense keeps each result on one line, though w and e still work
+        w.results.wrap = app.config.wrap_titles && !app.config.dense;
+        w.results.spacing = app.config.row_spacing && !app.config.dense;
+        w.results.dim_remakes = app.config.dim_remakes;
+        app.client = app.config.client.default.to_owned();
         app.src = app.config.default_source.to_owned();
-        if let Some((i, theme)) = theme::find_theme(app.config.default_theme.to_owned()) {
+        // A theme given with --theme is only used for this run, and isn't saved
+        let theme_name = match &app.theme_override {
+            Some(name) => name.to_owned(),
+            None => app.config.default_theme.to_owned(),
+        };
+        if let Some((i, theme)) = theme::find_theme(theme_name) {
             w.theme.selected = i;
             app.theme = theme;
         }
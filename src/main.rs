@@ -1,24 +1,45 @@
-use std::{env, io::stdout};
+use std::{env, io::stdout, panic, process};
 
 use app::{run_app, App};
 use crossterm::{
     cursor::SetCursorStyle,
+    event::DisableMouseCapture,
+    style::Print,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
+use widget::theme::{find_theme, THEMES};
 
 mod app;
 mod client;
+mod clipboard;
 mod config;
+mod open;
+mod resume;
+mod seen;
 mod source;
 mod widget;
 
+// Save and restore the window title on terminals that keep a stack of them (xterm's XTWINOPS)
+static PUSH_TITLE: &str = "\x1b[22;0t";
+static POP_TITLE: &str = "\x1b[23;0t";
+
+fn restore_terminal() -> Result<(), Box<dyn std::error::Error>> {
+    disable_raw_mode()?;
+    stdout().execute(DisableMouseCapture)?;
+    stdout().execute(SetCursorStyle::DefaultUserShape)?;
+    stdout().execute(LeaveAlternateScreen)?;
+    stdout().execute(Print(POP_TITLE))?;
+    Ok(())
+}
+
 #[tokio::main()]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // TODO: Use real command line package
-    let args: Vec<String> = env::args().collect();
-    for arg in args {
+    let mut args = env::args().skip(1);
+    let mut app = App::default();
+    while let Some(arg) = args.next() {
         if arg == "--version" || arg == "-V" || arg == "-v" {
             println!(
                 "nyaa v{}",
@@ -26,21 +47,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             );
             return Ok(());
         }
+        if arg == "--config-dir" {
+            println!("{}", config::config_dir()?.display());
+            return Ok(());
+        }
+        if arg == "--offline" {
+            app.offline = true;
+        }
+        if arg == "--dry-run" {
+            app.dry_run = true;
+        }
+        if arg == "--theme" {
+            // Checked here so a typo doesn't get as far as opening the app
+            let name = args.next().unwrap_or_default();
+            if find_theme(name.to_owned()).is_none() {
+                let names: Vec<&str> = THEMES.iter().map(|t| t.name).collect();
+                return Err(format!(
+                    "Unknown theme \"{}\", expected one of: {}",
+                    name,
+                    names.join(", ")
+                )
+                .into());
+            }
+            app.theme_override = Some(name);
+        }
     }
+    // Restore the terminal before printing the panic message. Panics in background loads would
+    // otherwise leave the app running in a restored terminal, waiting on results that never come
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+        process::exit(1);
+    }));
+
     enable_raw_mode()?;
+    stdout().execute(Print(PUSH_TITLE))?;
     stdout().execute(EnterAlternateScreen)?;
     stdout().execute(SetCursorStyle::SteadyBar)?;
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::default();
-
-    run_app(&mut terminal, &mut app).await?;
+    let res = run_app(&mut terminal, &mut app).await;
 
-    disable_raw_mode()?;
-    stdout().execute(SetCursorStyle::DefaultUserShape)?;
-    stdout().execute(LeaveAlternateScreen)?;
+    restore_terminal()?;
     terminal.show_cursor()?;
+    for download in app.dry_runs.iter() {
+        println!("{}", download);
+    }
 
-    Ok(())
+    res
 }
@@ -0,0 +1,31 @@
+use std::{
+    env,
+    error::Error,
+    process::{Command, Stdio},
+};
+
+// Without a display there's nothing for xdg-open to open things in, but it may not fail either
+pub fn has_desktop() -> bool {
+    cfg!(any(target_os = "windows", target_os = "macos"))
+        || env::var_os("DISPLAY").is_some()
+        || env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+// Open a url or path with whatever the system uses for it, without waiting for it to close
+pub fn open(target: &str) -> Result<(), Box<dyn Error>> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = Command::new("cmd");
+    #[cfg(target_os = "windows")]
+    cmd.args(["/C", "start", ""]);
+    #[cfg(target_os = "macos")]
+    let mut cmd = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = Command::new("xdg-open");
+
+    cmd.arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+// The search on screen when the app was closed, to pick up from on the next launch
+#[derive(Serialize, Deserialize)]
+pub struct LastSearch {
+    pub query: String,
+    pub page: usize,
+}
+
+fn path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::cache_dir()?.join("last_search.json"))
+}
+
+// Nothing to resume if the app hasn't been closed with `resume_search` on yet
+pub fn load() -> Result<Option<LastSearch>, Box<dyn Error>> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+pub fn store(last: &LastSearch) -> Result<(), Box<dyn Error>> {
+    let path = path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(last)?)?;
+    Ok(())
+}
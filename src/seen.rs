@@ -0,0 +1,37 @@
+use std::{collections::HashSet, error::Error, fs, path::PathBuf};
+
+use crate::{config, source::Item};
+
+fn path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::cache_dir()?.join("seen.json"))
+}
+
+// Torrents are remembered by info hash, which stays the same when they're reposted
+pub fn key(item: &Item) -> String {
+    match item.info_hash.is_empty() {
+        true => item.torrent_link.to_owned(),
+        false => item.info_hash.to_owned(),
+    }
+}
+
+// Nothing has been seen yet if the file doesn't exist
+pub fn load() -> Result<HashSet<String>, Box<dyn Error>> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn store(seen: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+    let path = path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Sorted, so the file doesn't churn on every write
+    let mut keys: Vec<&String> = seen.iter().collect();
+    keys.sort();
+    fs::write(path, serde_json::to_string(&keys)?)?;
+    Ok(())
+}
@@ -1,15 +1,19 @@
 use std::error::Error;
 
 use regex::Regex;
+use reqwest::{header::CONTENT_TYPE, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     app::{App, LoadType, Widgets},
-    widget::{category::CatIcon, EnumIter},
+    config::Config,
+    widget::{category::CatIcon, filter::Filter, sort::Sort, EnumIter},
 };
 
-use self::{nyaa_html::NyaaHtmlSource, nyaa_rss::NyaaRssSource};
+use self::{fixture::FixtureSource, nyaa_html::NyaaHtmlSource, nyaa_rss::NyaaRssSource};
 
+pub mod cache;
+pub mod fixture;
 pub mod nyaa_html;
 pub mod nyaa_rss;
 
@@ -27,29 +31,97 @@ pub fn add_protocol<S: Into<String>>(url: S, default_https: bool) -> String {
     }
 }
 
-#[derive(Clone)]
+// Get the lowercase info hash from the `xt=urn:btih:` part of a magnet link
+pub fn info_hash_from_magnet(magnet: &str) -> String {
+    magnet
+        .split(['?', '&'])
+        .find_map(|param| param.strip_prefix("xt=urn:btih:"))
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+// Give a clear message if nyaa.si rate-limits or blocks the request, instead of failing to parse
+pub fn check_response(
+    url: &str,
+    res: &Response,
+    expected: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let status = res.status();
+    let code = status.as_u16();
+    let challenge = res.headers().contains_key("cf-mitigated");
+    if challenge
+        || matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN | StatusCode::SERVICE_UNAVAILABLE
+        )
+    {
+        return Err(format!(
+            "{}\nnyaa.si is rate-limiting or blocked by Cloudflare (HTTP {}).\n{}",
+            url, code, "Wait a bit before trying again, or use a mirror for `base_url`."
+        )
+        .into());
+    }
+    if status != StatusCode::OK {
+        return Err(format!("{}\nInvalid response code: {}", url, code).into());
+    }
+    let actual = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !actual.is_empty() && !actual.contains(expected) {
+        return Err(format!(
+            "{}\nExpected {} but got \"{}\" (HTTP {}).\n{}",
+            url, expected, actual, code, "nyaa.si may be rate-limiting or blocked by Cloudflare."
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Item {
     pub index: usize,
     pub date: String,
+    #[serde(default)]
+    pub timestamp: i64, // Seconds since the epoch, since date is only for display
     pub seeders: u32,
     pub leechers: u32,
     pub downloads: u32,
+    pub comments: u32,
     pub size: String,
     pub bytes: usize,
     pub title: String,
     pub torrent_link: String,
+    pub post_link: String,
     pub magnet_link: String,
+    pub info_hash: String,
     pub file_name: String,
     pub category: usize,
+    #[serde(skip)]
     pub icon: CatIcon,
     pub trusted: bool,
     pub remake: bool,
+    #[serde(default)]
+    pub extensions: Vec<(String, String)>, // Raw values from the feed, shown when debugging
+}
+
+impl Item {
+    // Seeders per leecher. Nobody leeching counts as the best, unless nobody is seeding either
+    pub fn ratio(&self) -> f64 {
+        match (self.seeders, self.leechers) {
+            (0, 0) => 0.0,
+            (_, 0) => f64::INFINITY,
+            (s, l) => s as f64 / l as f64,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Sources {
     NyaaHtml,
     NyaaRss,
+    Fixture, // Bundled sample results, only used with `--offline`
 }
 
 impl EnumIter<Sources> for Sources {
@@ -59,42 +131,135 @@ impl EnumIter<Sources> for Sources {
     }
 }
 
+impl Sources {
+    // nyaa always returns 75 results per page, and ignores any size in the url
+    pub fn page_size(&self) -> usize {
+        match self {
+            Sources::NyaaHtml | Sources::NyaaRss | Sources::Fixture => 75,
+        }
+    }
+}
+
 impl ToString for Sources {
     fn to_string(&self) -> String {
         match self {
             Sources::NyaaHtml => "Nyaa HTML".to_owned(),
             Sources::NyaaRss => "Nyaa RSS".to_owned(),
+            Sources::Fixture => "Offline".to_owned(),
         }
     }
 }
 
+// A snapshot of everything needed to load results, so loads can run in the background
+#[derive(Clone)]
+pub struct SearchQuery {
+    pub query: String,
+    pub page: usize,
+    pub category: usize,
+    pub filter: Filter,
+    pub sort: Sort,
+    pub ascending: bool,
+    pub page_size: usize,
+    pub items: Vec<Item>, // Current results, for sorting in memory
+}
+
+impl SearchQuery {
+    pub fn new(app: &App, w: &Widgets) -> Self {
+        SearchQuery {
+            query: w.search.query(app),
+            page: app.page,
+            category: w.category.category,
+            filter: w.filter.selected.to_owned(),
+            sort: w.sort.selected.to_owned(),
+            ascending: app.ascending,
+            page_size: app.src.page_size(),
+            items: w.results.all_items.to_owned(),
+        }
+    }
+}
+
+pub struct SourceResults {
+    pub items: Vec<Item>,
+    pub last_page: usize,
+    pub total_results: usize,
+    pub skipped: usize, // Items that couldn't be parsed
+}
+
 pub trait Source {
-    async fn search(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>>;
-    async fn sort(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>>;
-    async fn filter(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>>;
-    async fn categorize(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>>;
+    async fn search(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>>;
+    async fn sort(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>>;
+    async fn filter(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>>;
+    async fn categorize(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>>;
+}
+
+// Sorting the RSS feed and the offline fixture happens in memory, so there's nothing to cache
+pub fn cacheable(src: Sources, load_type: LoadType) -> bool {
+    !matches!(
+        load_type,
+        LoadType::Downloading
+            | LoadType::DownloadingAll
+            | LoadType::Files
+            | LoadType::TestingClient
+    ) && !matches!((src, load_type), (Sources::NyaaRss, LoadType::Sorting))
+        && !matches!(src, Sources::Fixture)
 }
 
 pub async fn load(
     src: Sources,
     load_type: LoadType,
-    app: &mut App,
-    w: &Widgets,
-) -> Result<Vec<Item>, Box<dyn Error>> {
+    search: &SearchQuery,
+    config: &Config,
+) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
     match src {
         Sources::NyaaHtml => match load_type {
-            LoadType::Searching => NyaaHtmlSource::search(app, w).await,
-            LoadType::Sorting => NyaaHtmlSource::sort(app, w).await,
-            LoadType::Filtering => NyaaHtmlSource::filter(app, w).await,
-            LoadType::Categorizing => NyaaHtmlSource::categorize(app, w).await,
-            LoadType::Downloading => Ok(w.results.table.items.clone()),
+            LoadType::Searching
+            | LoadType::Resetting
+            | LoadType::Reloading
+            | LoadType::Refreshing => NyaaHtmlSource::search(search, config).await,
+            LoadType::Sorting => NyaaHtmlSource::sort(search, config).await,
+            LoadType::Filtering | LoadType::ClearingFilters => {
+                NyaaHtmlSource::filter(search, config).await
+            }
+            LoadType::Categorizing => NyaaHtmlSource::categorize(search, config).await,
+            LoadType::Downloading
+            | LoadType::DownloadingAll
+            | LoadType::Files
+            | LoadType::TestingClient => NyaaHtmlSource::search(search, config).await,
         },
         Sources::NyaaRss => match load_type {
-            LoadType::Searching => NyaaRssSource::search(app, w).await,
-            LoadType::Sorting => NyaaRssSource::sort(app, w).await,
-            LoadType::Filtering => NyaaRssSource::filter(app, w).await,
-            LoadType::Categorizing => NyaaRssSource::categorize(app, w).await,
-            LoadType::Downloading => Ok(w.results.table.items.clone()),
+            LoadType::Searching
+            | LoadType::Resetting
+            | LoadType::Reloading
+            | LoadType::Refreshing => NyaaRssSource::search(search, config).await,
+            LoadType::Sorting => NyaaRssSource::sort(search, config).await,
+            LoadType::Filtering | LoadType::ClearingFilters => {
+                NyaaRssSource::filter(search, config).await
+            }
+            LoadType::Categorizing => NyaaRssSource::categorize(search, config).await,
+            LoadType::Downloading
+            | LoadType::DownloadingAll
+            | LoadType::Files
+            | LoadType::TestingClient => NyaaRssSource::search(search, config).await,
+        },
+        Sources::Fixture => match load_type {
+            LoadType::Sorting => FixtureSource::sort(search, config).await,
+            LoadType::Filtering | LoadType::ClearingFilters => {
+                FixtureSource::filter(search, config).await
+            }
+            LoadType::Categorizing => FixtureSource::categorize(search, config).await,
+            _ => FixtureSource::search(search, config).await,
         },
     }
 }
@@ -0,0 +1,98 @@
+use std::{error::Error, fs, path::PathBuf, time::Duration};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{config, widget::category::CatEntry};
+
+use super::{Item, SearchQuery, SourceResults, Sources};
+
+#[derive(Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub timestamp: i64,
+    pub last_page: usize,
+    pub total_results: usize,
+    pub items: Vec<Item>,
+}
+
+// 64-bit FNV-1a. Unlike std's hashers, it gives the same keys with every Rust version
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Identifies a single page of results for the current query
+pub fn key(src: Sources, base_url: &str, search: &SearchQuery) -> String {
+    let id = [
+        src.to_string(),
+        base_url.to_owned(),
+        search.query.to_owned(),
+        search.category.to_string(),
+        (search.filter.clone() as u16).to_string(),
+        search.sort.to_string(),
+        search.ascending.to_string(),
+        search.page.to_string(),
+    ]
+    .join("\n");
+    format!("{:016x}", fnv1a(id.as_bytes()))
+}
+
+fn dir() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::cache_dir()?.join("results"))
+}
+
+fn path(key: &str) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(dir()?.join(format!("{}.json", key)))
+}
+
+// Returns the cached entry for `key` if it is younger than `ttl` seconds
+pub fn read(key: &str, ttl: u64) -> Option<SourceResults> {
+    let content = fs::read_to_string(path(key).ok()?).ok()?;
+    let mut entry: CacheEntry = serde_json::from_str(&content).ok()?;
+    if Local::now().timestamp() - entry.timestamp > ttl as i64 {
+        return None;
+    }
+    // Icons aren't stored, so get them from the category
+    for item in entry.items.iter_mut() {
+        item.icon = CatEntry::from_id(item.category).icon.clone();
+    }
+    Some(SourceResults {
+        items: entry.items,
+        last_page: entry.last_page,
+        total_results: entry.total_results,
+        skipped: 0,
+    })
+}
+
+// Remove entries older than `ttl` seconds, which would never be read again
+fn prune(ttl: u64) -> Result<(), Box<dyn Error>> {
+    let ttl = Duration::from_secs(ttl);
+    for entry in fs::read_dir(dir()?)?.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > ttl);
+        if expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+pub fn write(key: &str, results: &SourceResults, ttl: u64) -> Result<(), Box<dyn Error>> {
+    let path = path(key)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let entry = CacheEntry {
+        timestamp: Local::now().timestamp(),
+        last_page: results.last_page,
+        total_results: results.total_results,
+        items: results.items.to_owned(),
+    };
+    fs::write(path, serde_json::to_string(&entry)?)?;
+    prune(ttl)
+}
@@ -0,0 +1,84 @@
+use std::error::Error;
+
+use crate::{config::Config, widget::filter::Filter};
+
+use super::{
+    add_protocol,
+    nyaa_rss::{add_trackers, parse_feed, sort_items, NYAA_EXT},
+    SearchQuery, Source, SourceResults,
+};
+
+// Sample nyaa RSS feed used by `--offline`, so the app can run without network access
+static FIXTURE: &str = include_str!("../../fixtures/nyaa.xml");
+
+pub struct FixtureSource;
+
+impl Source for FixtureSource {
+    async fn search(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        let base_url = add_protocol(config.base_url.clone(), true);
+        let (items, skipped) = parse_feed(
+            FIXTURE.as_bytes(),
+            Some(NYAA_EXT),
+            &base_url,
+            &config.date_format,
+        )?;
+
+        let query = search.query.to_lowercase();
+        let (high, low) = (search.category / 10, search.category % 10);
+        let mut items: Vec<_> = items
+            .into_iter()
+            .filter(|i| {
+                let title = i.title.to_lowercase();
+                query
+                    .split_whitespace()
+                    .all(|word| match word.strip_prefix('-') {
+                        Some(excluded) if !excluded.is_empty() => !title.contains(excluded),
+                        _ => title.contains(word),
+                    })
+            })
+            .filter(|i| match (high, low) {
+                (0, _) => true,
+                (_, 0) => i.category / 10 == high,
+                _ => i.category == search.category,
+            })
+            .filter(|i| match search.filter {
+                Filter::NoRemakes => !i.remake,
+                Filter::TrustedOnly => i.trusted,
+                // The feed doesn't say which torrents are batches
+                Filter::NoFilter | Filter::Batches => true,
+            })
+            .collect();
+        add_trackers(&mut items, &config.trackers);
+        sort_items(&mut items, search.sort.clone(), search.ascending);
+        Ok(SourceResults {
+            total_results: items.len(),
+            items,
+            last_page: 1,
+            skipped,
+        })
+    }
+
+    async fn sort(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        FixtureSource::search(search, config).await
+    }
+
+    async fn filter(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        FixtureSource::search(search, config).await
+    }
+
+    async fn categorize(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        FixtureSource::search(search, config).await
+    }
+}
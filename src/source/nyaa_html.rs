@@ -1,16 +1,22 @@
 use std::{error::Error, time::Duration};
 
 use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
-use reqwest::StatusCode;
 use scraper::{ElementRef, Html, Selector};
 use urlencoding::encode;
 
 use crate::{
-    app::{App, Widgets},
-    widget::category::CatEntry,
+    config::Config,
+    widget::{
+        category::{self, CatEntry},
+        sort::Sort,
+    },
 };
 
-use super::{add_protocol, Item, Source};
+use super::{
+    add_protocol, check_response, info_hash_from_magnet,
+    nyaa_rss::{add_trackers, sort_items},
+    Item, SearchQuery, Source, SourceResults,
+};
 
 pub struct NyaaHtmlSource;
 
@@ -24,7 +30,7 @@ pub fn to_bytes(size: &str) -> usize {
         'G' => 9,
         'M' => 6,
         'K' => 3,
-        _ => 1,
+        _ => 0,
     };
     (f64::powi(10.0, power) * f) as usize
 }
@@ -44,74 +50,172 @@ fn attr(e: ElementRef, s: &Selector, attr: &str) -> String {
         .to_owned()
 }
 
+// Add each file and folder under `list` to `files`, indented by depth
+fn walk_files(list: ElementRef, depth: usize, files: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    for li in list.children().filter_map(ElementRef::wrap) {
+        let mut children = li.children().filter_map(ElementRef::wrap);
+        match children.find(|c| c.value().classes().any(|c| c == "folder")) {
+            Some(folder) => {
+                let name = folder.text().collect::<String>();
+                files.push(format!("{}{}/", indent, name.trim()));
+                let sub = li.children().filter_map(ElementRef::wrap);
+                for ul in sub.filter(|c| c.value().name() == "ul") {
+                    walk_files(ul, depth + 1, files);
+                }
+            }
+            None => {
+                // The name is the bare text, followed by the size in a span
+                let name: String = li
+                    .children()
+                    .filter_map(|c| c.value().as_text().map(|t| t.to_string()))
+                    .collect();
+                let size = li
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .find(|c| c.value().classes().any(|c| c == "file-size"))
+                    .map(|c| c.text().collect::<String>())
+                    .unwrap_or_default();
+                files.push(format!("{}{} {}", indent, name.trim(), size.trim()));
+            }
+        }
+    }
+}
+
+// The results page on the website, the same one the HTML source reads
+pub fn search_url(search: &SearchQuery, config: &Config) -> String {
+    let base_url = add_protocol(config.base_url.clone(), true);
+    let ord = match search.ascending {
+        true => "asc",
+        false => "desc",
+    };
+    format!(
+        "{}/?q={}&c={}&f={}&p={}&s={}&o={}",
+        base_url,
+        encode(&search.query),
+        category::to_code(search.category),
+        search.filter.clone() as u16,
+        search.page,
+        search.sort.to_url(),
+        ord
+    )
+}
+
+// For searches, pagination has a description of total results found
+fn total(doc: &Html) -> Result<Option<usize>, String> {
+    let pagination_sel = &sel(".pagination-page-info")?;
+    Ok(doc.select(pagination_sel).next().and_then(|pagination| {
+        // 6th word in pagination description contains total number of results
+        pagination.inner_html().split(' ').nth(5)?.parse().ok()
+    }))
+}
+
+// Only the "out of N" in the pagination is read, to show how many results a search has while
+// it's still being typed
+pub async fn count(
+    search: &SearchQuery,
+    config: &Config,
+) -> Result<usize, Box<dyn Error + Send + Sync>> {
+    let url = search_url(search, config);
+    let client = reqwest::Client::builder()
+        .gzip(true)
+        .timeout(Duration::from_secs(config.timeout))
+        .build()?;
+    let response = client.get(url.to_owned()).send().await?;
+    check_response(&url, &response, "html")?;
+    let content = response.bytes().await?;
+    let doc = Html::parse_document(std::str::from_utf8(&content[..])?);
+    // Without pagination, everything fits on the one page
+    let item_sel = &sel("table.torrent-list > tbody > tr")?;
+    Ok(total(&doc)?.unwrap_or(doc.select(item_sel).count()))
+}
+
+// Get the file tree from a torrent's page on nyaa
+pub async fn get_files(
+    post_link: &str,
+    timeout: u64,
+) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+    let client = reqwest::Client::builder()
+        .gzip(true)
+        .timeout(Duration::from_secs(timeout))
+        .build()?;
+    let response = client.get(post_link).send().await?;
+    check_response(post_link, &response, "html")?;
+    let content = response.bytes().await?;
+    let doc = Html::parse_document(std::str::from_utf8(&content[..])?);
+
+    let list_sel = &sel("div.torrent-file-list > ul")?;
+    let mut files = vec![];
+    if let Some(list) = doc.select(list_sel).next() {
+        walk_files(list, 0, &mut files);
+    }
+    if files.is_empty() {
+        return Err(format!("{}\nNo files found for this torrent", post_link).into());
+    }
+    Ok(files)
+}
+
+// scraper's selector errors borrow the input and aren't Send, so keep only the message
+fn sel(selectors: &str) -> Result<Selector, String> {
+    Selector::parse(selectors).map_err(|e| e.to_string())
+}
+
 impl Source for NyaaHtmlSource {
-    async fn filter(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        NyaaHtmlSource::search(app, w).await
+    async fn filter(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        NyaaHtmlSource::search(search, config).await
     }
-    async fn categorize(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        NyaaHtmlSource::search(app, w).await
+    async fn categorize(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        NyaaHtmlSource::search(search, config).await
     }
-    async fn sort(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        NyaaHtmlSource::search(app, w).await
+    async fn sort(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        NyaaHtmlSource::search(search, config).await
     }
-    async fn search(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        let cat = w.category.category;
-        let filter = w.filter.selected.clone() as u16;
-        let page = app.page;
-        let sort = w.sort.selected.to_url();
-        let timeout = app.config.timeout;
-
-        let base_url = add_protocol(app.config.base_url.clone(), true);
-        let (high, low) = (cat / 10, cat % 10);
-        let query = encode(&w.search.input.input);
-        let ord = match app.ascending {
-            true => "asc",
-            false => "desc",
-        };
-        let url = format!(
-            "{}/?q={}&c={}_{}&f={}&p={}&s={}&o={}",
-            base_url, query, high, low, filter, page, sort, ord
-        );
+    async fn search(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        let base_url = add_protocol(config.base_url.clone(), true);
+        let url = search_url(search, config);
+        let timeout = config.timeout;
 
         let client = reqwest::Client::builder()
             .gzip(true)
             .timeout(Duration::from_secs(timeout))
             .build()?;
         let response = client.get(url.to_owned()).send().await?;
-        if response.status() != StatusCode::OK {
-            // Throw error if response code is not OK
-            let code = response.status().as_u16();
-            return Err(format!("{}\nInvalid repsponse code: {}", url, code).into());
-        }
+        check_response(&url, &response, "html")?;
         let content = response.bytes().await?;
         let doc = Html::parse_document(std::str::from_utf8(&content[..])?);
 
-        let item_sel = &Selector::parse("table.torrent-list > tbody > tr")?;
-        let icon_sel = &Selector::parse("td:first-of-type > a")?;
-        let title_sel = &Selector::parse("td:nth-of-type(2) > a:last-of-type")?;
-        let torrent_sel = &Selector::parse("td:nth-of-type(3) > a:nth-of-type(1)")?;
-        let magnet_sel = &Selector::parse("td:nth-of-type(3) > a:nth-of-type(2)")?;
-        let size_sel = &Selector::parse("td:nth-of-type(4)")?;
-        let date_sel = &Selector::parse("td:nth-of-type(5)").unwrap();
-        let seed_sel = &Selector::parse("td:nth-of-type(6)")?;
-        let leech_sel = &Selector::parse("td:nth-of-type(7)")?;
-        let dl_sel = &Selector::parse("td:nth-of-type(8)")?;
-        let pagination_sel = &Selector::parse(".pagination-page-info")?;
-
-        app.last_page = 100;
-        app.total_results = 7500;
-        // For searches, pagination has a description of total results found
-        if let Some(pagination) = doc.select(pagination_sel).next() {
-            // 6th word in pagination description contains total number of results
-            if let Some(num_results_str) = pagination.inner_html().split(' ').nth(5) {
-                if let Ok(num_results) = num_results_str.parse::<usize>() {
-                    app.last_page = (num_results + 74) / 75;
-                    app.total_results = num_results;
-                }
-            }
+        let item_sel = &sel("table.torrent-list > tbody > tr")?;
+        let icon_sel = &sel("td:first-of-type > a")?;
+        let title_sel = &sel("td:nth-of-type(2) > a:last-of-type")?;
+        let comments_sel = &sel("td:nth-of-type(2) > a.comments")?;
+        let torrent_sel = &sel("td:nth-of-type(3) > a:nth-of-type(1)")?;
+        let magnet_sel = &sel("td:nth-of-type(3) > a:nth-of-type(2)")?;
+        let size_sel = &sel("td:nth-of-type(4)")?;
+        let date_sel = &sel("td:nth-of-type(5)")?;
+        let seed_sel = &sel("td:nth-of-type(6)")?;
+        let leech_sel = &sel("td:nth-of-type(7)")?;
+        let dl_sel = &sel("td:nth-of-type(8)")?;
+
+        let mut last_page = 100;
+        let mut total_results = 100 * search.page_size;
+        if let Some(num_results) = total(&doc)? {
+            last_page = (num_results + search.page_size - 1) / search.page_size;
+            total_results = num_results;
         }
 
-        Ok(doc
+        let mut items: Vec<Item> = doc
             .select(item_sel)
             .enumerate()
             .map(|(index, e)| {
@@ -133,30 +237,56 @@ impl Source for NyaaHtmlSource {
                 let naive =
                     NaiveDateTime::parse_from_str(&date, "%Y-%m-%d %H:%M").unwrap_or_default();
                 let date_time: DateTime<Local> = Local.from_utc_datetime(&naive);
-                let date = date_time.format(&app.config.date_format).to_string();
+                let date = date_time.format(&config.date_format).to_string();
+
+                let magnet_link = attr(e, magnet_sel, "href");
+                let info_hash = info_hash_from_magnet(&magnet_link);
 
                 let seeders = inner(e, seed_sel, "0").parse().unwrap_or(0);
                 let leechers = inner(e, leech_sel, "0").parse().unwrap_or(0);
                 let downloads = inner(e, dl_sel, "0").parse().unwrap_or(0);
+                // Only present when there are comments, next to a comment icon
+                let comments = e
+                    .select(comments_sel)
+                    .next()
+                    .map(|c| c.text().collect::<String>())
+                    .and_then(|c| c.trim().parse().ok())
+                    .unwrap_or(0);
 
                 Item {
                     index,
                     date,
+                    timestamp: date_time.timestamp(),
                     seeders,
                     leechers,
                     downloads,
+                    comments,
                     size,
                     bytes,
                     title: attr(e, title_sel, "title"),
                     torrent_link: format!("{}{}", base_url, torrent),
-                    magnet_link: attr(e, magnet_sel, "href"),
+                    post_link: format!("{}{}", base_url, attr(e, title_sel, "href")),
+                    magnet_link,
+                    info_hash,
                     file_name: file_name.to_owned(),
                     category,
                     icon,
                     trusted: e.value().classes().any(|e| e == "success"),
                     remake: e.value().classes().any(|e| e == "danger"),
+                    extensions: vec![],
                 }
             })
-            .collect())
+            .collect();
+        add_trackers(&mut items, &config.trackers);
+        // nyaa can't sort by ratio, so sort each page after it's fetched
+        if search.sort == Sort::Ratio {
+            sort_items(&mut items, Sort::Ratio, search.ascending);
+        }
+        Ok(SourceResults {
+            items,
+            last_page,
+            total_results,
+            skipped: 0,
+        })
     }
 }
@@ -5,14 +5,22 @@ use rss::{extension::Extension, Channel};
 use urlencoding::encode;
 
 use crate::{
-    app::{App, Widgets},
-    widget::{category::CatEntry, sort::Sort},
+    config::Config,
+    widget::{
+        category::{self, CatEntry},
+        sort::Sort,
+    },
 };
 
-use super::{add_protocol, nyaa_html::to_bytes, Item, Source};
+use super::{
+    add_protocol, check_response, nyaa_html::to_bytes, Item, SearchQuery, Source, SourceResults,
+};
 
 pub struct NyaaRssSource;
 
+pub static NYAA_EXT: &str = "https://nyaa.si/xmlns/nyaa";
+static NYAA_PREFIX: &str = "nyaa"; // Used when the feed doesn't declare the namespace
+
 type ExtensionMap = BTreeMap<String, Vec<Extension>>;
 
 pub fn get_ext_value<T: Default + FromStr>(ext_map: &ExtensionMap, key: &str) -> T {
@@ -24,13 +32,14 @@ pub fn get_ext_value<T: Default + FromStr>(ext_map: &ExtensionMap, key: &str) ->
         .unwrap_or_default()
 }
 
-fn sort_items(items: &mut [Item], sort: Sort, ascending: bool) {
+pub fn sort_items(items: &mut [Item], sort: Sort, ascending: bool) {
     let f: fn(&Item, &Item) -> Ordering = match sort {
         Sort::Date => |a, b| a.index.cmp(&b.index),
         Sort::Downloads => |a, b| b.downloads.cmp(&a.downloads),
         Sort::Seeders => |a, b| b.seeders.cmp(&a.seeders),
         Sort::Leechers => |a, b| b.leechers.cmp(&a.leechers),
         Sort::Size => |a, b| b.bytes.cmp(&a.bytes),
+        Sort::Ratio => |a, b| b.ratio().total_cmp(&a.ratio()),
     };
     items.sort_by(f);
     if ascending {
@@ -38,91 +47,487 @@ fn sort_items(items: &mut [Item], sort: Sort, ascending: bool) {
     }
 }
 
-impl Source for NyaaRssSource {
-    async fn sort(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        let mut items = w.results.table.items.clone();
-        sort_items(&mut items, w.sort.selected.clone(), app.ascending);
-        Ok(items)
-    }
-
-    async fn search(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        let cat = w.category.category;
-        let query = w.search.input.input.clone();
-        let filter = w.filter.selected.clone() as usize;
-        app.last_page = 1;
-        app.page = 1;
-        let (high, low) = (cat / 10, cat % 10);
-        let query = encode(&query);
-        let base_url = add_protocol(app.config.base_url.clone(), true);
-
-        let url = format!(
-            "{}/?page=rss&f={}&c={}_{}&q={}&m",
-            base_url, filter, high, low, query
-        );
-        let content = reqwest::get(url.clone()).await?.bytes().await?;
-
-        let client = reqwest::Client::builder()
-            .gzip(true)
-            .timeout(Duration::from_secs(app.config.timeout))
-            .build()?;
-        let response = client.get(url.to_owned()).send().await?;
-        let code = response.status().as_u16();
-        if code != 200 {
-            // Throw error if response code is not OK
-            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+// Build a magnet from the info hash when there isn't one, and add any configured trackers it's
+// missing, for clients that don't look for peers on their own
+pub fn add_trackers(items: &mut [Item], trackers: &[String]) {
+    for item in items.iter_mut().filter(|i| !i.info_hash.is_empty()) {
+        if !item.magnet_link.starts_with("magnet:") {
+            item.magnet_link = format!(
+                "magnet:?xt=urn:btih:{}&dn={}",
+                item.info_hash,
+                encode(&item.title)
+            );
+        }
+        for tracker in trackers.iter().map(|t| format!("tr={}", encode(t))) {
+            if !item.magnet_link.split(['?', '&']).any(|p| p == tracker) {
+                item.magnet_link.push('&');
+                item.magnet_link.push_str(&tracker);
+            }
         }
+    }
+}
 
-        let channel = Channel::read_from(&content[..])?;
+pub fn build_feed_url(base_url: &str, search: &SearchQuery) -> String {
+    format!(
+        "{}/?page=rss&f={}&c={}&q={}&m",
+        base_url,
+        search.filter.clone() as usize,
+        category::to_code(search.category),
+        encode(&search.query)
+    )
+}
 
-        let mut results: Vec<Item> = channel
-            .items
+pub async fn get_feed(url: &str, timeout: u64) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let client = reqwest::Client::builder()
+        .gzip(true)
+        .timeout(Duration::from_secs(timeout))
+        .build()?;
+    let response = client.get(url).send().await?;
+    // Cloudflare challenges and rate-limits return HTML instead of RSS
+    check_response(url, &response, "xml")?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+// Parse a raw RSS feed, see `parse_channel`
+pub fn parse_feed(
+    bytes: &[u8],
+    ext_ns: Option<&str>,
+    base_url: &str,
+    date_format: &str,
+) -> Result<(Vec<Item>, usize), Box<dyn Error + Send + Sync>> {
+    let channel = Channel::read_from(bytes)?;
+    Ok(parse_channel(&channel, ext_ns, base_url, date_format))
+}
+
+// Parse feed items, reading torrent info from the `ext_ns` extension namespace if the source has one.
+// Returns the items along with how many were skipped for missing the extension or a guid.
+pub fn parse_channel(
+    channel: &Channel,
+    ext_ns: Option<&str>,
+    base_url: &str,
+    date_format: &str,
+) -> (Vec<Item>, usize) {
+    let empty = ExtensionMap::new();
+    // Extensions are keyed by prefix, which is up to the feed, so find it from the namespace url
+    let prefix = ext_ns.map(|ns| {
+        channel
+            .namespaces()
             .iter()
-            .enumerate()
-            .filter_map(|(index, item)| {
-                let ext = item.extensions().get("nyaa")?;
-                let guid = item.guid()?;
-                let id = guid.value.rsplit('/').next().unwrap_or_default(); // Get nyaa id from guid url in format
-                                                                            // `https://nyaa.si/view/{id}`
-                let category_str = get_ext_value::<String>(ext, "categoryId");
-                let cat = CatEntry::from_str(&category_str);
-                let category = cat.id;
-                let icon = cat.icon.clone();
-                let size = get_ext_value::<String>(ext, "size")
-                    .replace('i', "")
-                    .replace("Bytes", "B");
-                let pub_date = item.pub_date().unwrap_or("");
-                let date = DateTime::parse_from_rfc2822(pub_date).unwrap_or_default();
-                let date = date.with_timezone(&Local);
-
-                Some(Item {
-                    index,
-                    date: date.format(&app.config.date_format).to_string(),
-                    seeders: get_ext_value(ext, "seeders"),
-                    leechers: get_ext_value(ext, "leechers"),
-                    downloads: get_ext_value(ext, "downloads"),
-                    bytes: to_bytes(&size),
-                    size,
-                    title: item.title().unwrap_or("???").to_owned(),
-                    torrent_link: format!("{}/download/{}.torrent", base_url, id),
-                    magnet_link: item.link().unwrap_or("???").to_owned(),
-                    file_name: format!("{}.torrent", id),
-                    trusted: get_ext_value::<String>(ext, "trusted").eq("Yes"),
-                    remake: get_ext_value::<String>(ext, "remake").eq("Yes"),
-                    category,
-                    icon,
-                })
+            .find(|(_, url)| *url == ns)
+            .map(|(prefix, _)| prefix.as_str())
+            .unwrap_or(NYAA_PREFIX)
+    });
+    let items: Vec<Item> = channel
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let ext = match prefix {
+                Some(prefix) => item.extensions().get(prefix)?,
+                None => &empty,
+            };
+            let guid = item.guid()?;
+            let id = guid.value.rsplit('/').next().unwrap_or_default(); // Get nyaa id from guid url in format
+                                                                        // `https://nyaa.si/view/{id}`
+            let category_str = get_ext_value::<String>(ext, "categoryId");
+            // Fall back to the category's name if the id isn't one nyaa has
+            let cat = CatEntry::from_code(&category_str)
+                .or_else(|| CatEntry::from_name(&get_ext_value::<String>(ext, "category")))
+                .unwrap_or_else(|| CatEntry::from_str(&category_str));
+            let category = cat.id;
+            let icon = cat.icon.clone();
+            let size = get_ext_value::<String>(ext, "size")
+                .replace('i', "")
+                .replace("Bytes", "B");
+            let pub_date = item.pub_date().unwrap_or("");
+            let date = DateTime::parse_from_rfc2822(pub_date).unwrap_or_default();
+            let date = date.with_timezone(&Local);
+
+            Some(Item {
+                index,
+                date: date.format(date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: get_ext_value(ext, "seeders"),
+                leechers: get_ext_value(ext, "leechers"),
+                downloads: get_ext_value(ext, "downloads"),
+                comments: get_ext_value(ext, "comments"),
+                bytes: to_bytes(&size),
+                size,
+                title: item.title().unwrap_or("???").to_owned(),
+                torrent_link: format!("{}/download/{}.torrent", base_url, id),
+                post_link: format!("{}/view/{}", base_url, id),
+                magnet_link: item.link().unwrap_or("???").to_owned(),
+                info_hash: get_ext_value::<String>(ext, "infoHash").to_lowercase(),
+                file_name: format!("{}.torrent", id),
+                trusted: get_ext_value::<String>(ext, "trusted").eq("Yes"),
+                remake: get_ext_value::<String>(ext, "remake").eq("Yes"),
+                extensions: ext
+                    .iter()
+                    .map(|(key, values)| {
+                        let value = values.first().and_then(|v| v.value()).unwrap_or_default();
+                        (key.to_owned(), value.to_owned())
+                    })
+                    .collect(),
+                category,
+                icon,
             })
-            .collect();
-        app.total_results = results.len();
-        sort_items(&mut results, w.sort.selected.clone(), app.ascending);
-        Ok(results)
+        })
+        .collect();
+    let skipped = channel.items.len() - items.len();
+    (items, skipped)
+}
+
+impl Source for NyaaRssSource {
+    async fn sort(
+        search: &SearchQuery,
+        _config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        let mut items = search.items.clone();
+        sort_items(&mut items, search.sort.clone(), search.ascending);
+        Ok(SourceResults {
+            total_results: items.len(),
+            items,
+            last_page: 1,
+            skipped: 0,
+        })
+    }
+
+    async fn search(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        let base_url = add_protocol(config.base_url.clone(), true);
+        let url = build_feed_url(&base_url, search);
+        let content = get_feed(&url, config.timeout).await?;
+
+        let (mut results, skipped) =
+            parse_feed(&content, Some(NYAA_EXT), &base_url, &config.date_format)?;
+        add_trackers(&mut results, &config.trackers);
+        sort_items(&mut results, search.sort.clone(), search.ascending);
+        Ok(SourceResults {
+            total_results: results.len(),
+            items: results,
+            last_page: 1,
+            skipped,
+        })
+    }
+
+    async fn filter(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        NyaaRssSource::search(search, config).await
+    }
+
+    async fn categorize(
+        search: &SearchQuery,
+        config: &Config,
+    ) -> Result<SourceResults, Box<dyn Error + Send + Sync>> {
+        NyaaRssSource::search(search, config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        source::SearchQuery,
+        widget::{category::find_category, filter::Filter, sort::Sort},
+    };
+
+    use super::{add_trackers, build_feed_url, parse_feed, NYAA_EXT};
+
+    static BASE_URL: &str = "https://nyaa.si";
+    static FIXTURE: &str = include_str!("../../fixtures/nyaa.xml");
+
+    fn feed(items: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<rss xmlns:nyaa="https://nyaa.si/xmlns/nyaa" version="2.0">
+<channel><title>Nyaa</title><link>https://nyaa.si/</link><description>Test</description>
+{}
+</channel></rss>"#,
+            items
+        )
+    }
+
+    #[test]
+    fn parses_fixture() {
+        let (items, skipped) =
+            parse_feed(FIXTURE.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert_eq!(items.len(), 10);
+        assert_eq!(skipped, 0);
+
+        let first = &items[0];
+        assert_eq!(first.index, 0);
+        assert_eq!(
+            first.title,
+            "[SubsPlease] Sousou no Frieren - 26 (1080p) [7F2C3B1A].mkv"
+        );
+        assert_eq!(first.date, "2024");
+        assert_eq!(first.seeders, 2311);
+        assert_eq!(first.leechers, 154);
+        assert_eq!(first.downloads, 18620);
+        assert_eq!(first.comments, 12);
+        assert_eq!(first.size, "1.4 GB");
+        assert_eq!(first.bytes, 1_400_000_000);
+        assert_eq!(first.category, 12);
+        assert_eq!(
+            first.torrent_link,
+            "https://nyaa.si/download/1000001.torrent"
+        );
+        assert_eq!(first.post_link, "https://nyaa.si/view/1000001");
+        assert_eq!(first.file_name, "1000001.torrent");
+        assert_eq!(first.info_hash, "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567");
+        assert!(first
+            .magnet_link
+            .starts_with("magnet:?xt=urn:btih:0a1b2c3d"));
+        assert!(first.trusted);
+        assert!(!first.remake);
+    }
+
+    #[test]
+    fn parses_zero_seeders_and_remakes() {
+        let (items, _) = parse_feed(FIXTURE.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        let remake = &items[2];
+        assert_eq!(remake.seeders, 0);
+        assert!(remake.remake);
+        assert!(!remake.trusted);
+    }
+
+    #[test]
+    fn defaults_missing_fields() {
+        let xml = feed(
+            r#"<item>
+<guid isPermaLink="true">https://nyaa.si/view/42</guid>
+<nyaa:categoryId>1_2</nyaa:categoryId>
+</item>"#,
+        );
+        let (items, skipped) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert_eq!(skipped, 0);
+        let item = &items[0];
+        assert_eq!(item.title, "???");
+        assert_eq!(item.magnet_link, "???");
+        assert_eq!(item.seeders, 0);
+        assert_eq!(item.leechers, 0);
+        assert_eq!(item.downloads, 0);
+        assert_eq!(item.comments, 0);
+        assert_eq!(item.bytes, 0);
+        assert_eq!(item.info_hash, "");
+        assert_eq!(item.file_name, "42.torrent");
+        assert!(!item.trusted);
+        assert!(!item.remake);
+    }
+
+    #[test]
+    fn falls_back_on_unknown_category() {
+        let xml = feed(
+            r#"<item>
+<guid>https://nyaa.si/view/1</guid>
+<nyaa:categoryId>9_9</nyaa:categoryId>
+</item>
+<item>
+<guid>https://nyaa.si/view/2</guid>
+<nyaa:categoryId>anime</nyaa:categoryId>
+</item>"#,
+        );
+        let (items, _) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert_eq!(items[0].category, 0); // All Categories
+        assert_eq!(items[1].category, 10); // Unparseable major defaults to Anime
+    }
+
+    #[test]
+    fn falls_back_on_category_name() {
+        let xml = feed(
+            r#"<item>
+<guid>https://nyaa.si/view/1</guid>
+<nyaa:categoryId>3_1</nyaa:categoryId>
+<nyaa:category>Anime - Raw</nyaa:category>
+</item>
+<item>
+<guid>https://nyaa.si/view/2</guid>
+<nyaa:categoryId>1-3</nyaa:categoryId>
+<nyaa:category>Anime - Non-English-translated</nyaa:category>
+</item>
+<item>
+<guid>https://nyaa.si/view/3</guid>
+<nyaa:category>Software - Games</nyaa:category>
+</item>"#,
+        );
+        let (items, _) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert_eq!(items[0].category, 31); // The id wins when it's valid
+        assert_eq!(items[1].category, 13);
+        assert_eq!(items[2].category, 62);
+    }
+
+    #[test]
+    fn skips_items_without_extension() {
+        let xml = feed(
+            r#"<item>
+<title>Not from nyaa</title>
+<guid>https://example.com/view/7</guid>
+</item>
+<item>
+<title>No guid</title>
+<nyaa:seeders>5</nyaa:seeders>
+</item>"#,
+        );
+        let (items, skipped) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert!(items.is_empty());
+        assert_eq!(skipped, 2);
+
+        // Sources without an extension still get the plain RSS fields
+        let (items, skipped) = parse_feed(xml.as_bytes(), None, BASE_URL, "%Y").unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(skipped, 1);
+        assert_eq!(items[0].title, "Not from nyaa");
+        assert_eq!(items[0].seeders, 0);
     }
 
-    async fn filter(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        NyaaRssSource::search(app, w).await
+    #[test]
+    fn finds_extension_by_namespace() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss xmlns:ny="https://nyaa.si/xmlns/nyaa" version="2.0">
+<channel><title>Nyaa</title><link>https://nyaa.si/</link><description>Test</description>
+<item>
+<guid>https://nyaa.si/view/3</guid>
+<ny:seeders>12</ny:seeders>
+<ny:trusted>Yes</ny:trusted>
+</item>
+</channel></rss>"#;
+        let (items, skipped) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(items[0].seeders, 12);
+        assert!(items[0].trusted);
     }
 
-    async fn categorize(app: &mut App, w: &Widgets) -> Result<Vec<Item>, Box<dyn Error>> {
-        NyaaRssSource::search(app, w).await
+    #[test]
+    fn rejects_invalid_feed() {
+        let html = "<html><body>Just a moment...</body></html>";
+        assert!(parse_feed(html.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").is_err());
+    }
+
+    #[test]
+    fn adds_missing_trackers() {
+        let xml = feed(
+            r#"<item>
+<title>No magnet</title>
+<guid>https://nyaa.si/view/7</guid>
+<nyaa:infoHash>ABCDEF</nyaa:infoHash>
+</item>"#,
+        );
+        let (mut items, _) = parse_feed(xml.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        let (mut fixture, _) =
+            parse_feed(FIXTURE.as_bytes(), Some(NYAA_EXT), BASE_URL, "%Y").unwrap();
+        items.push(fixture.remove(0));
+        let trackers = vec![
+            "http://nyaa.tracker.wf:7777/announce".to_owned(),
+            "udp://open.stealth.si:80/announce".to_owned(),
+        ];
+        add_trackers(&mut items, &trackers);
+        assert_eq!(
+            items[0].magnet_link,
+            "magnet:?xt=urn:btih:abcdef&dn=No%20magnet\
+             &tr=http%3A%2F%2Fnyaa.tracker.wf%3A7777%2Fannounce\
+             &tr=udp%3A%2F%2Fopen.stealth.si%3A80%2Fannounce"
+        );
+        // Trackers already in the magnet aren't repeated
+        assert_eq!(items[1].magnet_link.matches("nyaa.tracker.wf").count(), 1);
+        assert!(items[1]
+            .magnet_link
+            .ends_with("&tr=udp%3A%2F%2Fopen.stealth.si%3A80%2Fannounce"));
+    }
+
+    #[test]
+    fn builds_feed_url() {
+        let search = SearchQuery {
+            query: "frieren 1080p".to_owned(),
+            page: 1,
+            category: 12,
+            filter: Filter::TrustedOnly,
+            sort: Sort::Date,
+            ascending: false,
+            page_size: 75,
+            items: vec![],
+        };
+        assert_eq!(
+            build_feed_url(BASE_URL, &search),
+            "https://nyaa.si/?page=rss&f=2&c=1_2&q=frieren%201080p&m"
+        );
+    }
+
+    fn search(query: &str) -> SearchQuery {
+        SearchQuery {
+            query: query.to_owned(),
+            page: 1,
+            category: 0,
+            filter: Filter::NoFilter,
+            sort: Sort::Date,
+            ascending: false,
+            page_size: 75,
+            items: vec![],
+        }
+    }
+
+    #[test]
+    fn encodes_special_characters() {
+        // Quotes are kept for nyaa's exact match, and nothing can end the query early
+        let cases = [
+            ("\"sousou no frieren\"", "%22sousou%20no%20frieren%22"),
+            (
+                "葬送のフリーレン",
+                "%E8%91%AC%E9%80%81%E3%81%AE%E3%83%95%E3%83%AA%E3%83%BC%E3%83%AC%E3%83%B3",
+            ),
+            ("a&b#c+d", "a%26b%23c%2Bd"),
+            ("frieren|meshi -raw", "frieren%7Cmeshi%20-raw"),
+        ];
+        for (query, encoded) in cases {
+            assert!(
+                build_feed_url(BASE_URL, &search(query)).contains(&format!("&q={}&m", encoded)),
+                "{} should be encoded as {}",
+                query,
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn builds_category_codes() {
+        // Codes from the category dropdown on nyaa.si
+        let codes = [
+            ("AllCategories", "0_0"),
+            ("AllAnime", "1_0"),
+            ("AnimeMusicVideo", "1_1"),
+            ("AnimeEnglishTranslated", "1_2"),
+            ("AnimeNonEnglishTranslated", "1_3"),
+            ("AnimeRaw", "1_4"),
+            ("AllAudio", "2_0"),
+            ("AudioLossless", "2_1"),
+            ("AudioLossy", "2_2"),
+            ("AllLiterature", "3_0"),
+            ("LitEnglishTranslated", "3_1"),
+            ("LitNonEnglishTranslated", "3_2"),
+            ("LitRaw", "3_3"),
+            ("AllLiveAction", "4_0"),
+            ("LiveEnglishTranslated", "4_1"),
+            ("LiveIdolPromoVideo", "4_2"),
+            ("LiveNonEnglishTranslated", "4_3"),
+            ("LiveRaw", "4_4"),
+            ("AllPictures", "5_0"),
+            ("PicGraphics", "5_1"),
+            ("PicPhotos", "5_2"),
+            ("AllSoftware", "6_0"),
+            ("SoftApplications", "6_1"),
+            ("SoftGames", "6_2"),
+        ];
+        for (cfg, code) in codes {
+            let mut query = search("");
+            query.category = find_category(cfg).unwrap().id;
+            assert!(
+                build_feed_url(BASE_URL, &query).contains(&format!("&c={}&", code)),
+                "{} should be {}",
+                cfg,
+                code
+            );
+        }
     }
 }
@@ -5,25 +5,30 @@ use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize as _},
-    widgets::{Block, Borders, Clear, ScrollbarState, TableState, Widget as _},
+    widgets::{Block, BorderType, Borders, Clear, ScrollbarState, TableState, Widget as _},
     Frame,
 };
 
 use crate::app::App;
 
-use self::theme::Theme;
+use self::theme::Border;
 
 pub mod category;
 pub mod clients;
+pub mod confirm;
 pub mod error;
+pub mod files;
 pub mod filter;
 pub mod help;
 pub mod input;
+pub mod legend;
 pub mod page;
 pub mod results;
 pub mod search;
+pub mod settings;
 pub mod sort;
 pub mod sources;
+pub mod status;
 pub mod theme;
 
 pub trait Widget {
@@ -60,7 +65,16 @@ pub fn centered_rect(mut x_len: u16, mut y_len: u16, r: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
-pub fn border_block(theme: &Theme, focused: bool) -> Block {
+pub fn border_block(app: &App, focused: bool) -> Block {
+    let theme = app.theme;
+    let (borders, border_type) = match app.config.border {
+        Some(Border::Plain) => (Borders::ALL, BorderType::Plain),
+        Some(Border::Rounded) => (Borders::ALL, BorderType::Rounded),
+        Some(Border::Double) => (Borders::ALL, BorderType::Double),
+        Some(Border::Thick) => (Borders::ALL, BorderType::Thick),
+        Some(Border::None) => (Borders::NONE, theme.border),
+        None => (Borders::ALL, theme.border),
+    };
     Block::new()
         .border_style(match focused {
             true => Style::new().fg(theme.border_focused_color),
@@ -68,8 +82,8 @@ pub fn border_block(theme: &Theme, focused: bool) -> Block {
         })
         .bg(theme.bg)
         .fg(theme.fg)
-        .borders(Borders::ALL)
-        .border_type(theme.border)
+        .borders(borders)
+        .border_type(border_type)
 }
 
 pub fn clear(area: Rect, buf: &mut Buffer, fill: Color) {
@@ -104,7 +118,7 @@ impl<T> StatefulTable<T> {
         self.scrollbar_state = self.scrollbar_state.position(i as usize);
     }
 
-    pub fn next(&mut self, amt: isize) {
+    pub fn next_clamp(&mut self, amt: isize) {
         if self.items.is_empty() {
             return;
         }
@@ -117,6 +131,14 @@ impl<T> StatefulTable<T> {
         self.scrollbar_state = self.scrollbar_state.position(idx);
     }
 
+    // Stop at the ends unless `wrap_navigation` is on
+    pub fn next_nav(&mut self, amt: isize, wrap: bool) {
+        match wrap {
+            true => self.next_wrap(amt),
+            false => self.next_clamp(amt),
+        }
+    }
+
     pub fn select(&mut self, idx: usize) {
         self.state.select(Some(idx));
         self.scrollbar_state = self.scrollbar_state.position(idx);
@@ -1,7 +1,7 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Rect},
-    style::{Color, Stylize as _},
+    style::Stylize as _,
     text::{Line, Span, Text},
     widgets::{Row, Table, Widget as _},
     Frame,
@@ -21,15 +21,11 @@ pub struct CatEntry {
 #[derive(Clone)]
 pub struct CatIcon {
     pub label: &'static str,
-    pub color: Color,
 }
 
 impl Default for CatIcon {
     fn default() -> Self {
-        CatIcon {
-            label: "???",
-            color: Color::Gray,
-        }
+        CatIcon { label: "???" }
     }
 }
 
@@ -38,7 +34,43 @@ impl CatEntry {
         let split: Vec<&str> = s.split('_').collect();
         let high = split.first().unwrap_or(&"1").parse().unwrap_or(1);
         let low = split.last().unwrap_or(&"0").parse().unwrap_or(0);
+        CatEntry::from_id(high * 10 + low)
+    }
+
+    // Only an id nyaa actually has, like `1_2`, unlike `from_str` which always finds something
+    pub fn from_code(s: &str) -> Option<&'static Self> {
+        let (high, low) = s.trim().split_once('_')?;
+        let (high, low): (usize, usize) = (high.parse().ok()?, low.parse().ok()?);
+        if low > 9 {
+            return None;
+        }
         let id = high * 10 + low;
+        ALL_CATEGORIES
+            .iter()
+            .find_map(|cat| cat.entries.iter().find(|ent| ent.id == id))
+    }
+
+    // The category's name as nyaa writes it, like `Anime - English-translated`. Case, spaces
+    // and dashes don't matter
+    pub fn from_name(s: &str) -> Option<&'static Self> {
+        let simplify = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let name = simplify(s);
+        if name.is_empty() {
+            return None;
+        }
+        ALL_CATEGORIES.iter().find_map(|cat| {
+            cat.entries
+                .iter()
+                .find(|ent| simplify(&format!("{} {}", cat.name, ent.name)) == name)
+        })
+    }
+
+    pub fn from_id(id: usize) -> &'static Self {
         for cat in ALL_CATEGORIES {
             if let Some(ent) = cat.entries.iter().find(|ent| ent.id == id) {
                 return ent;
@@ -48,19 +80,18 @@ impl CatEntry {
     }
 }
 
+// The `c=` code nyaa uses for a category id, e.g. 21 is Audio - Lossless, or `2_1`
+pub fn to_code(id: usize) -> String {
+    format!("{}_{}", id / 10, id % 10)
+}
+
 impl CatEntry {
-    const fn new(
-        name: &'static str,
-        cfg: &'static str,
-        id: usize,
-        label: &'static str,
-        color: Color,
-    ) -> Self {
+    const fn new(name: &'static str, cfg: &'static str, id: usize, label: &'static str) -> Self {
         CatEntry {
             name,
             cfg,
             id,
-            icon: CatIcon { label, color },
+            icon: CatIcon { label },
         }
     }
 }
@@ -72,119 +103,80 @@ pub struct CatStruct {
 
 pub static ALL: CatStruct = CatStruct {
     name: "All Categories",
-    entries: &[CatEntry::new(
-        "All Categories",
-        "AllCategories",
-        0,
-        "---",
-        Color::White,
-    )],
+    entries: &[CatEntry::new("All Categories", "AllCategories", 0, "---")],
 };
 
 pub static ANIME: CatStruct = CatStruct {
     name: "Anime",
     entries: &[
-        CatEntry::new("All Anime", "AllAnime", 10, "Ani", Color::Gray),
-        CatEntry::new(
-            "English Translated",
-            "AnimeEnglishTranslated",
-            12,
-            "Sub",
-            Color::LightMagenta,
-        ),
+        CatEntry::new("All Anime", "AllAnime", 10, "Ani"),
+        CatEntry::new("English Translated", "AnimeEnglishTranslated", 12, "Sub"),
         CatEntry::new(
             "Non-English Translated",
             "AnimeNonEnglishTranslated",
             13,
             "Sub",
-            Color::LightGreen,
-        ),
-        CatEntry::new("Raw", "AnimeRaw", 14, "Raw", Color::Gray),
-        CatEntry::new(
-            "Anime Music Video",
-            "AnimeMusicVideo",
-            11,
-            "AMV",
-            Color::Magenta,
         ),
+        CatEntry::new("Raw", "AnimeRaw", 14, "Raw"),
+        CatEntry::new("Anime Music Video", "AnimeMusicVideo", 11, "AMV"),
     ],
 };
 
 pub static AUDIO: CatStruct = CatStruct {
     name: "Audio",
     entries: &[
-        CatEntry::new("All Audio", "AllAudio", 20, "Aud", Color::Gray),
-        CatEntry::new("Lossless", "AudioLossless", 21, "Aud", Color::Red),
-        CatEntry::new("Lossy", "AudioLossy", 22, "Aud", Color::Yellow),
+        CatEntry::new("All Audio", "AllAudio", 20, "Aud"),
+        CatEntry::new("Lossless", "AudioLossless", 21, "Aud"),
+        CatEntry::new("Lossy", "AudioLossy", 22, "Aud"),
     ],
 };
 
 pub static LITERATURE: CatStruct = CatStruct {
     name: "Literature",
     entries: &[
-        CatEntry::new("All Literature", "AllLiterature", 30, "Lit", Color::Gray),
-        CatEntry::new(
-            "English-Translated",
-            "LitEnglishTranslated",
-            31,
-            "Lit",
-            Color::LightGreen,
-        ),
+        CatEntry::new("All Literature", "AllLiterature", 30, "Lit"),
+        CatEntry::new("English-Translated", "LitEnglishTranslated", 31, "Lit"),
         CatEntry::new(
             "Non-English Translated",
             "LitNonEnglishTranslated",
             32,
             "Lit",
-            Color::Yellow,
         ),
-        CatEntry::new("Raw", "LitRaw", 33, "Lit", Color::Green),
+        CatEntry::new("Raw", "LitRaw", 33, "Lit"),
     ],
 };
 
 pub static LIVE_ACTION: CatStruct = CatStruct {
     name: "Live Action",
     entries: &[
-        CatEntry::new("All Live Action", "AllLiveAction", 40, "Liv", Color::Gray),
-        CatEntry::new(
-            "English-Translated",
-            "LiveEnglishTranslated",
-            41,
-            "Liv",
-            Color::Yellow,
-        ),
+        CatEntry::new("All Live Action", "AllLiveAction", 40, "Liv"),
+        CatEntry::new("English-Translated", "LiveEnglishTranslated", 41, "Liv"),
         CatEntry::new(
             "Non-English Translated",
             "LiveNonEnglishTranslated",
             43,
             "Liv",
-            Color::LightCyan,
         ),
-        CatEntry::new(
-            "Idol/Promo Video",
-            "LiveIdolPromoVideo",
-            42,
-            "Liv",
-            Color::LightYellow,
-        ),
-        CatEntry::new("Raw", "LiveRaw", 44, "Liv", Color::Gray),
+        CatEntry::new("Idol/Promo Video", "LiveIdolPromoVideo", 42, "Liv"),
+        CatEntry::new("Raw", "LiveRaw", 44, "Liv"),
     ],
 };
 
 pub static PICTURES: CatStruct = CatStruct {
     name: "Pictures",
     entries: &[
-        CatEntry::new("All Pictures", "AllPictures", 50, "Pic", Color::Gray),
-        CatEntry::new("Graphics", "PicGraphics", 51, "Pic", Color::LightMagenta),
-        CatEntry::new("Photos", "PicPhotos", 52, "Pic", Color::Magenta),
+        CatEntry::new("All Pictures", "AllPictures", 50, "Pic"),
+        CatEntry::new("Graphics", "PicGraphics", 51, "Pic"),
+        CatEntry::new("Photos", "PicPhotos", 52, "Pic"),
     ],
 };
 
 pub static SOFTWARE: CatStruct = CatStruct {
     name: "Software",
     entries: &[
-        CatEntry::new("All Software", "AllSoftware", 60, "Sof", Color::Gray),
-        CatEntry::new("Applications", "SoftApplications", 61, "Sof", Color::Blue),
-        CatEntry::new("Games", "SoftGames", 62, "Sof", Color::LightBlue),
+        CatEntry::new("All Software", "AllSoftware", 60, "Sof"),
+        CatEntry::new("Applications", "SoftApplications", 61, "Sof"),
+        CatEntry::new("Games", "SoftGames", 62, "Sof"),
     ],
 };
 
@@ -235,6 +227,33 @@ impl CategoryPopup {
         };
         self.minor = 0;
     }
+
+    // `[` and `]` while browsing results jump straight to the previous or next group's
+    // "All" category, without opening the popup
+    pub fn quick_switch(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char(c @ ('[' | ']')),
+            kind: KeyEventKind::Press,
+            ..
+        }) = e
+        {
+            self.major = ALL_CATEGORIES
+                .iter()
+                .position(|cat| cat.entries.iter().any(|ent| ent.id == self.category))
+                .unwrap_or(0);
+            match c {
+                ']' => self.next_tab(),
+                _ => self.prev_tab(),
+            }
+            if let Some(ent) = ALL_CATEGORIES
+                .get(self.major)
+                .and_then(|cat| cat.entries.first())
+            {
+                self.category = ent.id;
+            }
+            app.mode = Mode::Loading(LoadType::Categorizing);
+        }
+    }
 }
 
 impl Widget for CategoryPopup {
@@ -257,12 +276,12 @@ impl Widget for CategoryPopup {
                         true => "  ",
                         false => "   ",
                     }),
-                    e.icon.label.fg(e.icon.color),
+                    e.icon.label.fg(app.theme.category_color(e.id)),
                     Span::raw(" "),
                     Span::raw(e.name),
                 ])]);
                 match i == self.minor {
-                    true => row.bg(app.theme.hl_bg),
+                    true => row.bg(app.theme.hl_bg).fg(app.theme.hl_fg),
                     false => row,
                 }
             });
@@ -273,7 +292,7 @@ impl Widget for CategoryPopup {
             let clear = super::centered_rect(center.width + 2, center.height, area);
             super::clear(clear, f.buffer_mut(), app.theme.bg);
             Table::new(tbl, [Constraint::Percentage(100)])
-                .block(border_block(app.theme, true).title("Category"))
+                .block(border_block(app, true).title("Category"))
                 .render(center, f.buffer_mut());
         }
     }
@@ -282,11 +301,13 @@ impl Widget for CategoryPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Enter => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Enter, &KeyModifiers::NONE) => {
                     if let Some(cat) = ALL_CATEGORIES.get(self.major) {
                         if let Some(item) = cat.entries.get(self.minor) {
                             self.category = item.id;
@@ -294,12 +315,14 @@ impl Widget for CategoryPopup {
                     }
                     app.mode = Mode::Loading(LoadType::Categorizing);
                 }
-                KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('q') => {
+                (Esc | Char('c') | Char('q'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    let last = self.major + 1 >= ALL_CATEGORIES.len();
                     if let Some(cat) = ALL_CATEGORIES.get(self.major) {
                         self.minor = match self.minor + 1 >= cat.entries.len() {
+                            true if last && !app.config.wrap_navigation => self.minor,
                             true => {
                                 self.next_tab();
                                 0
@@ -308,9 +331,10 @@ impl Widget for CategoryPopup {
                         };
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                (Char('k') | Up, &KeyModifiers::NONE) => {
                     if ALL_CATEGORIES.get(self.major).is_some() {
                         self.minor = match self.minor < 1 {
+                            true if self.major == 0 && !app.config.wrap_navigation => 0,
                             true => {
                                 self.prev_tab();
                                 match ALL_CATEGORIES.get(self.major) {
@@ -322,18 +346,18 @@ impl Widget for CategoryPopup {
                         };
                     }
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     if let Some(cat) = ALL_CATEGORIES.get(self.major) {
                         self.minor = cat.entries.len() - 1;
                     }
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.minor = 0;
                 }
-                KeyCode::Tab | KeyCode::Char('J') => {
+                (Tab, &KeyModifiers::NONE) | (Char('J'), &KeyModifiers::SHIFT) => {
                     self.next_tab();
                 }
-                KeyCode::BackTab | KeyCode::Char('K') => {
+                (BackTab, _) | (Char('K'), &KeyModifiers::SHIFT) => {
                     self.prev_tab();
                 }
                 _ => {}
@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
@@ -7,7 +7,7 @@ use ratatui::{
 };
 
 use crate::{
-    app::{App, Mode},
+    app::{App, LoadType, Mode},
     client::Client,
 };
 
@@ -38,8 +38,8 @@ impl Widget for ClientsPopup {
         });
         super::clear(clear, buf, app.theme.bg);
         let table = Table::new(items, [Constraint::Percentage(100)])
-            .block(border_block(app.theme, true).title("Download Client"))
-            .highlight_style(Style::default().bg(app.theme.hl_bg));
+            .block(border_block(app, true).title("Download Client"))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
         table.render(center, buf, &mut self.table.state.to_owned());
     }
 
@@ -47,30 +47,44 @@ impl Widget for ClientsPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('d') | KeyCode::Char('q') => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc | Char('d') | Char('q'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                (Char('t'), &KeyModifiers::NONE) => {
+                    app.mode = Mode::Loading(LoadType::TestingClient);
+                }
+                (Enter, &KeyModifiers::NONE) => {
                     if let Some(c) = Client::iter().nth(self.table.state.selected().unwrap_or(0)) {
                         app.client = *c;
+                        let mut keys = vec!["client.default".to_owned()];
+                        if !c.has_config(&app.config.client) {
+                            keys.push(format!("client.{}", c.to_string()));
+                        }
                         if let Err(e) = c.load_config(app) {
                             app.show_error(e);
+                        } else if let Err(e) = app.config.store_keys(&keys) {
+                            app.show_error(format!(
+                                "Failed to update default client in config file:\n{}",
+                                e
+                            ));
                         }
                         app.mode = Mode::Normal;
                     }
@@ -83,6 +97,7 @@ impl Widget for ClientsPopup {
     fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
         Some(vec![
             ("Enter", "Confirm"),
+            ("t", "Test connection"),
             ("Esc, d, q", "Close"),
             ("j, ↓", "Down"),
             ("k, ↑", "Up"),
@@ -0,0 +1,123 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Rect},
+    text::{Line, Text},
+    widgets::{Paragraph, Widget as _, Wrap},
+    Frame,
+};
+
+use crate::{
+    app::{App, LoadType, Mode},
+    config::Config,
+};
+
+use super::{border_block, Widget};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Confirm {
+    SaveDefaults,
+    Quit,
+    DownloadAll(usize), // Number of results shown
+}
+
+impl Confirm {
+    fn title(&self) -> &'static str {
+        match self {
+            Confirm::SaveDefaults => "Reset to Defaults",
+            Confirm::Quit => "Quit",
+            Confirm::DownloadAll(_) => "Download All",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Confirm::SaveDefaults => {
+                "Selections were reset. Also save defaults to the config file?".to_owned()
+            }
+            Confirm::Quit => "Are you sure you want to quit?".to_owned(),
+            Confirm::DownloadAll(count) => {
+                format!("Download all {} results shown on this page?", count)
+            }
+        }
+    }
+
+    fn accept(&self, app: &mut App) {
+        match self {
+            Confirm::SaveDefaults => {
+                let default = Config::default();
+                app.config.default_category = default.default_category;
+                app.config.default_filter = default.default_filter;
+                app.config.default_sort = default.default_sort;
+                app.config.default_theme = default.default_theme;
+                let keys = [
+                    "default_category",
+                    "default_filter",
+                    "default_sort",
+                    "default_theme",
+                ];
+                if let Err(e) = app.config.store_keys(&keys) {
+                    app.show_error(format!("Failed to write defaults to config file:\n{}", e));
+                }
+                app.mode = Mode::Normal;
+            }
+            Confirm::Quit => app.quit(),
+            Confirm::DownloadAll(_) => app.mode = Mode::Loading(LoadType::DownloadingAll),
+        }
+    }
+
+    fn decline(&self, app: &mut App) {
+        match self {
+            Confirm::SaveDefaults | Confirm::Quit | Confirm::DownloadAll(_) => app.pop_mode(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ConfirmPopup;
+
+impl Widget for ConfirmPopup {
+    fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
+        if let Mode::Confirm(confirm) = app.mode {
+            let buf = f.buffer_mut();
+            let center = super::centered_rect(40, 6, area);
+            let clear = super::centered_rect(center.width + 2, center.height, area);
+            let text = Text::from(vec![
+                Line::from(confirm.message()),
+                Line::from(""),
+                Line::from("(y)es / (n)o").alignment(Alignment::Center),
+            ]);
+            let p = Paragraph::new(text)
+                .block(border_block(app, true).title(confirm.title()))
+                .wrap(Wrap { trim: false });
+            super::clear(clear, buf, app.theme.bg);
+            p.render(center, buf);
+        }
+    }
+
+    fn handle_event(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            use KeyCode::*;
+            if let Mode::Confirm(confirm) = app.mode {
+                match (code, modifiers) {
+                    (Char('y') | Enter, &KeyModifiers::NONE) => {
+                        confirm.accept(app);
+                    }
+                    (Esc | Char('n') | Char('q'), &KeyModifiers::NONE) => {
+                        confirm.decline(app);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+        Some(vec![("y, Enter", "Yes"), ("n, Esc, q", "No")])
+    }
+}
@@ -1,4 +1,7 @@
-use std::cmp::{max, min};
+use std::{
+    cell::Cell,
+    cmp::{max, min},
+};
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
@@ -8,17 +11,22 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{App, Mode};
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::app::App;
 
 use super::{border_block, Widget};
 
 pub struct ErrorPopup {
     pub error: String,
+    scroll: u16,         // Lines scrolled down, for errors taller than the terminal
+    overflow: Cell<u16>, // Lines that didn't fit when last drawn
 }
 
 impl ErrorPopup {
     pub fn with_error(&mut self, error: String) {
         self.error = error;
+        self.scroll = 0;
     }
 }
 
@@ -26,6 +34,8 @@ impl Default for ErrorPopup {
     fn default() -> Self {
         ErrorPopup {
             error: "".to_owned(),
+            scroll: 0,
+            overflow: Cell::new(0),
         }
     }
 }
@@ -33,22 +43,26 @@ impl Default for ErrorPopup {
 impl Widget for ErrorPopup {
     fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
         let lines = self.error.split('\n');
-        let max_line = lines.clone().fold(30, |acc, e| max(e.len(), acc)) as u16 + 3;
-        let x_len = min(max_line, area.width - 4);
+        let max_line = lines.clone().fold(30, |acc, e| max(e.width(), acc)) as u16 + 3;
+        let x_len = min(max_line, area.width.saturating_sub(4));
 
         // Get number of lines including wrapped lines
         let height = lines.fold(0, |acc, e| {
-            acc + (e.len() as f32 / (x_len - 2) as f32).ceil() as u16
+            acc + (e.width() as f32 / x_len.saturating_sub(2).max(1) as f32).ceil() as u16
         }) + 2;
         let center = super::centered_rect(x_len, height, area);
         let clear = super::centered_rect(center.width + 2, center.height, area);
+        // Errors that don't fit scroll instead
+        let overflow = height.saturating_sub(center.height);
+        self.overflow.set(overflow);
+        let title = match overflow {
+            0 => "Error: Press any key to dismiss",
+            _ => "Error: ↑/↓ to scroll, any key to dismiss",
+        };
         let p = Paragraph::new(self.error.to_owned())
-            .block(
-                border_block(app.theme, true)
-                    .fg(app.theme.remake)
-                    .title("Error: Press any key to dismiss"),
-            )
-            .wrap(Wrap { trim: false });
+            .block(border_block(app, true).fg(app.theme.remake).title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
         super::clear(clear, f.buffer_mut(), app.theme.bg);
         p.render(center, f.buffer_mut());
     }
@@ -61,9 +75,11 @@ impl Widget for ErrorPopup {
         }) = e
         {
             match code {
+                KeyCode::Down => self.scroll = min(self.scroll + 1, self.overflow.get()),
+                KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
                 KeyCode::Esc | KeyCode::Char(_) => {
                     if app.errors.is_empty() {
-                        app.mode = Mode::Normal;
+                        app.pop_mode();
                     }
                 }
                 _ => {}
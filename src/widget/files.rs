@@ -0,0 +1,103 @@
+use std::cmp::{max, min};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Style,
+    widgets::{Row, StatefulWidget as _, Table},
+    Frame,
+};
+
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::app::{App, Mode};
+
+use super::{border_block, StatefulTable, Widget};
+
+pub struct FilesPopup {
+    pub table: StatefulTable<String>,
+    pub title: String,
+}
+
+impl FilesPopup {
+    pub fn with_files(&mut self, title: String, files: Vec<String>) {
+        self.table = StatefulTable::with_items(files);
+        self.title = title;
+    }
+}
+
+impl Default for FilesPopup {
+    fn default() -> Self {
+        FilesPopup {
+            table: StatefulTable::with_items(vec![]),
+            title: "".to_owned(),
+        }
+    }
+}
+
+impl Widget for FilesPopup {
+    fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
+        let buf = f.buffer_mut();
+        let longest = self
+            .table
+            .items
+            .iter()
+            .map(|i| i.width())
+            .max()
+            .unwrap_or(0);
+        let width = min(max(longest, self.title.width()) + 4, area.width as usize);
+        let height = min(self.table.items.len() + 2, area.height as usize);
+        let center = super::centered_rect(width as u16, height as u16, area);
+        let clear = super::centered_rect(center.width + 2, center.height, area);
+        let items = self
+            .table
+            .items
+            .iter()
+            .map(|item| Row::new(vec![format!(" {}", item)]));
+        super::clear(clear, buf, app.theme.bg);
+        let table = Table::new(items, [Constraint::Percentage(100)])
+            .block(border_block(app, true).title(self.title.to_owned()))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
+        table.render(center, buf, &mut self.table.state.to_owned());
+    }
+
+    fn handle_event(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc | Char('v') | Char('q'), &KeyModifiers::NONE) => {
+                    app.mode = Mode::Normal;
+                }
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
+                }
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
+                }
+                (Char('G'), &KeyModifiers::SHIFT) => {
+                    self.table.select(max(self.table.items.len(), 1) - 1);
+                }
+                (Char('g'), &KeyModifiers::NONE) => {
+                    self.table.select(0);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+        Some(vec![
+            ("Esc, v, q", "Close"),
+            ("j, ↓", "Down"),
+            ("k, ↑", "Up"),
+            ("g", "Top"),
+            ("G", "Bottom"),
+        ])
+    }
+}
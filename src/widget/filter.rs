@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
@@ -69,8 +69,8 @@ impl Widget for FilterPopup {
         });
         super::clear(clear, f.buffer_mut(), app.theme.bg);
         Table::new(items, [Constraint::Percentage(100)])
-            .block(border_block(app.theme, true).title("Filter"))
-            .highlight_style(Style::default().bg(app.theme.hl_bg))
+            .block(border_block(app, true).title("Filter"))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg))
             .render(center, f.buffer_mut(), &mut self.table.state.to_owned());
     }
 
@@ -78,26 +78,28 @@ impl Widget for FilterPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc | Char('f') | Char('q'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                (Enter, &KeyModifiers::NONE) => {
                     if let Some(i) =
                         Filter::iter().nth(self.table.state.selected().unwrap_or_default())
                     {
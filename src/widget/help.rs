@@ -1,21 +1,26 @@
 use std::cmp::{max, min};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint, Margin, Rect},
     style::{Modifier, Style, Stylize},
     text::Line,
-    widgets::{Row, Scrollbar, ScrollbarOrientation, StatefulWidget as _, Table},
+    widgets::{Paragraph, Row, Scrollbar, ScrollbarOrientation, StatefulWidget as _, Table},
     Frame,
 };
 
+use unicode_width::UnicodeWidthStr as _;
+
 use crate::app::{App, Mode};
 
-use super::{border_block, StatefulTable, Widget};
+use super::{border_block, input::InputWidget, StatefulTable, Widget};
 
 pub struct HelpPopup {
     pub table: StatefulTable<(&'static str, &'static str)>,
     pub prev_mode: Mode,
+    all_items: Vec<(&'static str, &'static str)>,
+    input: InputWidget,
+    searching: bool,
 }
 
 impl Default for HelpPopup {
@@ -23,29 +28,67 @@ impl Default for HelpPopup {
         HelpPopup {
             table: StatefulTable::with_items(vec![]),
             prev_mode: Mode::Normal,
+            all_items: vec![],
+            input: InputWidget::new(30, None),
+            searching: false,
         }
     }
 }
 
+fn global_help(mode: &Mode) -> Vec<(&'static str, &'static str)> {
+    vec![
+        match mode {
+            Mode::Search => ("F1", "Help"),
+            _ => ("?, F1", "Help"),
+        },
+        ("Ctrl-c", "Exit App"),
+    ]
+}
+
 impl HelpPopup {
-    pub fn with_items(&mut self, items: Vec<(&'static str, &'static str)>, prev_mode: Mode) {
+    pub fn with_items(&mut self, mut items: Vec<(&'static str, &'static str)>, prev_mode: Mode) {
+        // Skip global bindings whose keys are all already listed for this mode
+        let keys: Vec<&str> = items.iter().flat_map(|(key, _)| key.split(", ")).collect();
+        for global in global_help(&prev_mode) {
+            if !global.0.split(", ").all(|k| keys.contains(&k)) {
+                items.push(global);
+            }
+        }
+        self.all_items = items;
+        self.prev_mode = prev_mode;
+        self.input.input = "".to_owned();
+        self.input.cursor = 0;
+        self.searching = false;
+        self.filter_items();
+    }
+
+    fn filter_items(&mut self) {
+        let query = self.input.input.to_lowercase();
+        let items: Vec<(&'static str, &'static str)> = self
+            .all_items
+            .iter()
+            .filter(|(key, map)| {
+                map.to_lowercase().contains(&query) || key.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .collect();
         self.table.scrollbar_state = self.table.scrollbar_state.content_length(items.len());
         self.table.items = items;
-        self.prev_mode = prev_mode;
+        self.table.select(0);
     }
 }
 
 impl Widget for HelpPopup {
     fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
         let buf = f.buffer_mut();
-        let iter = self.table.items.iter();
+        let iter = self.all_items.iter();
 
         // Get max len of Key
-        let key_min = iter.clone().fold(15, |acc, e| max(acc, e.0.len())) as u16;
+        let key_min = iter.clone().fold(15, |acc, e| max(acc, e.0.width())) as u16;
         // Get max len of action
-        let map_min = iter.fold(15, |acc, e| max(acc, e.1.len())) as u16;
-        // Cap height between the number of entries + 3 for padding, and 20
-        let height = min(25, self.table.items.len() + 3) as u16;
+        let map_min = iter.fold(15, |acc, e| max(acc, e.1.width())) as u16;
+        // Cap height between the number of entries + 3 for padding, and 25
+        let height = min(25, self.all_items.len() + 3) as u16;
 
         let center = super::centered_rect(key_min + map_min + 6, height, area);
         let clear = super::centered_rect(center.width + 2, center.height, area);
@@ -67,13 +110,10 @@ impl Widget for HelpPopup {
         .height(1)
         .bottom_margin(0);
         let table = Table::new(items, [Constraint::Percentage(100)])
-            .block(
-                border_block(app.theme, true)
-                    .title(format!("Help: {}", self.prev_mode.to_string())),
-            )
+            .block(border_block(app, true).title(format!("Help: {}", self.prev_mode.to_string())))
             .header(header)
             .widths(Constraint::from_lengths([key_min, 1, map_min]))
-            .highlight_style(Style::default().bg(app.theme.hl_bg));
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
 
         super::clear(clear, buf, app.theme.bg);
         table.render(center, buf, &mut self.table.state.to_owned());
@@ -91,29 +131,75 @@ impl Widget for HelpPopup {
             });
             sb.render(sb_area, buf, &mut self.table.scrollbar_state.to_owned());
         }
+
+        // Show filter on the bottom border while searching or filtered
+        if self.searching || !self.input.input.is_empty() {
+            let filter_area = Rect::new(
+                center.x + 1,
+                center.bottom().saturating_sub(1),
+                center.width.saturating_sub(2),
+                1,
+            );
+            f.render_widget(
+                Paragraph::new(format!("/{}", self.input.input)),
+                filter_area,
+            );
+            if self.searching {
+                let input_area = Rect::new(
+                    filter_area.x + 1,
+                    filter_area.y,
+                    filter_area.width.saturating_sub(1),
+                    1,
+                );
+                self.input.show_cursor(f, input_area);
+            }
+        }
     }
 
     fn handle_event(&mut self, app: &mut crate::app::App, e: &crossterm::event::Event) {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('?') | KeyCode::F(1) | KeyCode::Char('q') => {
-                    app.mode = self.prev_mode.to_owned();
+            use KeyCode::*;
+            if self.searching {
+                match (code, modifiers) {
+                    (Esc, &KeyModifiers::NONE) => {
+                        self.input.input = "".to_owned();
+                        self.input.cursor = 0;
+                        self.searching = false;
+                        self.filter_items();
+                    }
+                    (Enter, &KeyModifiers::NONE) => {
+                        self.searching = false;
+                    }
+                    _ => {
+                        self.input.handle_event(app, e);
+                        self.filter_items();
+                    }
+                }
+                return;
+            }
+            match (code, modifiers) {
+                (Esc | F(1) | Char('q'), &KeyModifiers::NONE) | (Char('?'), _) => {
+                    app.pop_mode();
+                }
+                (Char('/'), &KeyModifiers::NONE) => {
+                    self.searching = true;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
-                    self.table.select(self.table.items.len() - 1);
+                (Char('G'), &KeyModifiers::SHIFT) => {
+                    self.table.select(max(self.table.items.len(), 1) - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
                 _ => {}
@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Paragraph, Widget},
     Frame,
 };
-use unicode_width::UnicodeWidthChar;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::app::App;
 
@@ -29,7 +29,10 @@ impl InputWidget {
 
     pub fn show_cursor(&self, f: &mut Frame, area: Rect) {
         f.set_cursor(
-            min(area.x + self.cursor as u16, area.x + area.width - 1),
+            min(
+                area.x + self.cursor as u16,
+                area.x + area.width.saturating_sub(1),
+            ),
             area.y,
         );
     }
@@ -37,15 +40,19 @@ impl InputWidget {
 
 impl super::Widget for InputWidget {
     fn draw(&self, f: &mut Frame, _app: &App, area: Rect) {
-        let width = self.input.len();
+        let width = self.input.width();
         let fwidth = area.width as usize;
         // Try to insert ellipsis if input is too long (visual only)
         let visible = if width >= fwidth {
-            let idx = width - fwidth + 2;
-            match self.input.get(idx..) {
-                Some(sub) => format!("…{}", sub),
-                None => self.input.to_owned(),
+            // Keep whole characters from the end, since wide ones take up two columns
+            let mut tail = String::new();
+            for c in self.input.chars().rev() {
+                if tail.width() + c.width().unwrap_or(0) > fwidth.saturating_sub(2) {
+                    break;
+                }
+                tail.insert(0, c);
             }
+            format!("…{}", tail)
         } else {
             self.input.to_owned()
         };
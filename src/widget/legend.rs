@@ -0,0 +1,69 @@
+use crossterm::event::Event;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize as _},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::app::{App, Mode};
+
+use super::Widget;
+
+// Moving around is assumed knowledge, so it isn't worth the space
+static MOVEMENT: &[&str] = &["Top", "Bottom", "Up", "Down", "↓ Down", "↑ Up"];
+static MAX_KEYS: usize = 5;
+
+#[derive(Default)]
+pub struct LegendWidget {
+    pub items: Vec<(&'static str, &'static str)>, // Help for the current mode
+}
+
+impl Widget for LegendWidget {
+    fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
+        if self.items.is_empty() {
+            return;
+        }
+        let help = match app.mode {
+            Mode::Search => ("F1", "Help"),
+            _ => ("?", "Help"),
+        };
+        let sep = " · ";
+        let keys: Vec<(&str, &str)> = self
+            .items
+            .iter()
+            .filter(|(_, map)| !MOVEMENT.contains(map))
+            .map(|(key, map)| (key.split(", ").next().unwrap_or(key), *map))
+            .take(MAX_KEYS)
+            .collect();
+
+        // Keep as many keys as fit, always leaving room for help
+        let text_width = |(key, map): &(&str, &str)| key.width() + map.width() + 1;
+        let mut width = 1 + text_width(&help);
+        let mut spans = vec![Span::raw(" ")];
+        for key in keys.iter() {
+            width += text_width(key) + sep.width();
+            if width > area.width as usize {
+                break;
+            }
+            spans.push(Span::styled(key.0, Style::new().bold()));
+            spans.push(Span::raw(format!(" {}", key.1)));
+            spans.push(Span::styled(sep, Style::new().fg(app.theme.border_color)));
+        }
+        spans.push(Span::styled(help.0, Style::new().bold()));
+        spans.push(Span::raw(format!(" {}", help.1)));
+
+        let p = Paragraph::new(Line::from(spans))
+            .bg(app.theme.bg)
+            .fg(app.theme.fg);
+        f.render_widget(p, area);
+    }
+
+    fn handle_event(&mut self, _app: &mut App, _e: &Event) {}
+
+    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+        None
+    }
+}
@@ -1,10 +1,13 @@
 use std::cmp::{max, min};
 
 use crate::app::{App, LoadType, Mode};
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
-    layout::{Margin, Rect},
-    widgets::{Paragraph, Widget as _},
+    layout::{Alignment, Margin, Rect},
+    widgets::{
+        block::{Position, Title},
+        Paragraph, Widget as _,
+    },
     Frame,
 };
 
@@ -32,7 +35,12 @@ impl Widget for PagePopup {
         let center = super::centered_rect(13, 3, area);
         let clear = super::centered_rect(center.width + 2, center.height, area);
         let page_p = Paragraph::new(self.input.input.clone());
-        let indicator = Paragraph::new(">").block(border_block(app.theme, true).title("Goto Page"));
+        // Show the valid pages, so there's no need to guess
+        let range = Title::from(format!("1 - {}", app.last_page.max(1)))
+            .position(Position::Bottom)
+            .alignment(Alignment::Center);
+        let indicator =
+            Paragraph::new(">").block(border_block(app, true).title("Goto Page").title(range));
         super::clear(clear, buf, app.theme.bg);
         indicator.render(center, buf);
 
@@ -57,14 +65,16 @@ impl Widget for PagePopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc, &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Enter => {
+                (Enter, &KeyModifiers::NONE) => {
                     app.page = max(min(self.input.input.parse().unwrap_or(1), app.last_page), 1);
                     app.mode = Mode::Loading(LoadType::Searching);
 
@@ -1,36 +1,534 @@
-use std::cmp::max;
+use std::{
+    cmp::{max, min},
+    collections::{HashMap, HashSet},
+    ops::Range,
+    time::Duration,
+};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::{
-    layout::{Constraint, Margin, Rect},
+    layout::{Alignment, Constraint, Margin, Rect},
     style::{Modifier, Style, Stylize},
-    text::Text,
+    text::{Line, Span, Text},
     widgets::{
-        Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation, StatefulWidget, Table, Widget,
+        Borders, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation, StatefulWidget, Table,
+        TableState, Widget,
     },
     Frame,
 };
 
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
 use crate::{
     app::{App, LoadType, Mode},
+    clipboard, config, open, seen,
     source::Item,
-    widget::sort::SortDir,
+    widget::{confirm::Confirm, sort::SortDir},
 };
 
 use super::{border_block, centered_rect, sort::Sort, StatefulTable};
 
+// Client-side view of trusted uploads, separate from the server-side filter
+#[derive(PartialEq, Clone, Copy)]
+pub enum TrustedView {
+    All,
+    Dim,
+    Only,
+}
+
+impl TrustedView {
+    fn next(self) -> Self {
+        match self {
+            TrustedView::All => TrustedView::Dim,
+            TrustedView::Dim => TrustedView::Only,
+            TrustedView::Only => TrustedView::All,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ColumnsConfig {
+    pub category: bool,
+    pub size: bool,
+    pub date: bool,
+    pub seeders: bool,
+    pub leechers: bool,
+    pub downloads: bool,
+    pub comments: bool,
+    pub ratio: bool,
+    pub info_hash: bool,
+}
+
+impl Default for ColumnsConfig {
+    fn default() -> Self {
+        ColumnsConfig {
+            category: true,
+            size: true,
+            date: true,
+            seeders: true,
+            leechers: true,
+            downloads: true,
+            comments: false,
+            ratio: false,
+            info_hash: false,
+        }
+    }
+}
+
+impl ColumnsConfig {
+    fn visible(&self) -> Vec<Column> {
+        [
+            (Column::Category, self.category),
+            (Column::Name, true), // Always shown
+            (Column::Size, self.size),
+            (Column::Date, self.date),
+            (Column::Seeders, self.seeders),
+            (Column::Leechers, self.leechers),
+            (Column::Downloads, self.downloads),
+            (Column::Comments, self.comments),
+            (Column::Ratio, self.ratio),
+            (Column::InfoHash, self.info_hash),
+        ]
+        .into_iter()
+        .filter(|(_, show)| *show)
+        .map(|(c, _)| c)
+        .collect()
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Column {
+    Category,
+    Name,
+    Size,
+    Date,
+    Seeders,
+    Leechers,
+    Downloads,
+    Comments,
+    Ratio,
+    InfoHash,
+}
+
+impl Column {
+    // Name takes up whatever width is left over
+    fn width(&self, date_width: u16) -> u16 {
+        match self {
+            Column::Category => 3,
+            Column::Name => 0,
+            Column::Size => 9,
+            Column::Date => date_width,
+            Column::Seeders | Column::Leechers | Column::Comments => 4,
+            Column::Downloads => 5,
+            Column::Ratio => 7,
+            Column::InfoHash => 40,
+        }
+    }
+}
+
+// What Enter does with the selected result
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterAction {
+    Download,
+    CopyMagnet,
+    OpenBrowser,
+}
+
 pub struct ResultsWidget {
     pub table: StatefulTable<Item>,
+    pub all_items: Vec<Item>, // Everything fetched, including rows hidden from the table
+    pub min_seeders: u32,
+    pub dedup: bool,
+    pub wrap: bool,    // Wrap long titles instead of cutting them off
+    pub spacing: bool, // Blank line between rows
+    pub dim_remakes: bool,
+    trusted_view: TrustedView,
+    hidden: usize,
+    merged: usize,
+    pub skipped: usize,   // Feed items the source couldn't parse
+    count: Option<usize>, // Pending count prefix
     sort: Sort,
+    ascending: bool,
+    pub area: Rect,           // Where the table was last drawn, for mouse clicks
+    pub filters: Vec<String>, // Filters applied by the source, which could be hiding results
+    pub blocked: Option<&'static str>, // Why nothing was searched
+    pub seen: HashSet<String>, // Torrents downloaded or opened before, see `seen::key`
+    pub deltas: HashMap<String, i64>, // Change in seeders since the last refresh, by `seen::key`
 }
 
 impl ResultsWidget {
-    pub fn with_items(&mut self, items: Vec<Item>, sort: Sort) {
-        let len = items.len();
-        self.table.items = items;
-        self.table.select(0);
-        self.table.scrollbar_state = self.table.scrollbar_state.content_length(len);
+    pub fn with_items(&mut self, items: Vec<Item>, sort: Sort, ascending: bool) {
+        self.all_items = items;
         self.sort = sort;
+        self.ascending = ascending;
+        self.update_view(None);
+    }
+
+    // Reverse the results if only the direction of their sort changed. Returns whether it did
+    pub fn flip_sort(&mut self, sort: &Sort, ascending: bool) -> bool {
+        if self.sort != *sort || self.ascending == ascending {
+            return false;
+        }
+        let selected = self.selected_link();
+        self.ascending = ascending;
+        self.all_items.reverse();
+        self.update_view(selected);
+        true
+    }
+
+    // Compare seeders with the results on screen, for torrents that are in both
+    pub fn track_seeders(&mut self, items: &[Item]) {
+        let prev: HashMap<String, u32> = self
+            .all_items
+            .iter()
+            .map(|i| (seen::key(i), i.seeders))
+            .collect();
+        self.deltas = items
+            .iter()
+            .filter_map(|i| {
+                let key = seen::key(i);
+                let delta = i.seeders as i64 - *prev.get(&key)? as i64;
+                Some((key, delta)).filter(|_| delta != 0)
+            })
+            .collect();
+    }
+
+    // Replace items while keeping the same torrent selected, if it's still there
+    pub fn refresh_items(&mut self, items: Vec<Item>) {
+        let selected = self.selected_link();
+        self.track_seeders(&items);
+        self.all_items = items;
+        self.update_view(selected);
+    }
+
+    // Only show items that pass the view filters, without fetching again
+    fn update_view(&mut self, selected: Option<String>) {
+        let (min_seeders, trusted_only) =
+            (self.min_seeders, self.trusted_view == TrustedView::Only);
+        // The same torrent can be reposted, so keep only the first of each info hash
+        let mut seen = HashSet::new();
+        let unique: Vec<&Item> = self
+            .all_items
+            .iter()
+            .filter(|i| !self.dedup || i.info_hash.is_empty() || seen.insert(&i.info_hash))
+            .collect();
+        self.merged = self.all_items.len() - unique.len();
+        self.table.items = unique
+            .iter()
+            .filter(|i| i.seeders >= min_seeders && (i.trusted || !trusted_only))
+            .map(|i| (*i).to_owned())
+            .collect();
+        self.hidden = unique.len() - self.table.items.len();
+        let idx = selected
+            .and_then(|link| self.table.items.iter().position(|i| i.torrent_link == link))
+            .unwrap_or(0);
+        self.table.scrollbar_state = self
+            .table
+            .scrollbar_state
+            .content_length(self.table.items.len());
+        self.table.select(idx);
+    }
+
+    fn selected_link(&self) -> Option<String> {
+        self.table
+            .state
+            .selected()
+            .and_then(|i| self.table.items.get(i))
+            .map(|i| i.torrent_link.to_owned())
+    }
+
+    fn toggle_min_seeders(&mut self, app: &App) {
+        self.min_seeders = match self.min_seeders {
+            0 => app.config.min_seeders.max(1),
+            _ => 0,
+        };
+        self.update_view(self.selected_link());
+    }
+
+    fn toggle_trusted(&mut self) {
+        self.trusted_view = self.trusted_view.next();
+        self.update_view(self.selected_link());
+    }
+
+    // Select the next trusted item in `dir`, skipping the rest
+    fn next_trusted(&mut self, dir: isize, wrap: bool) {
+        let len = self.table.items.len() as isize;
+        let mut idx = self.table.state.selected().unwrap_or(0) as isize;
+        for _ in 1..len {
+            idx += dir;
+            if !(0..len).contains(&idx) {
+                if !wrap {
+                    return;
+                }
+                idx = idx.rem_euclid(len);
+            }
+            if self.table.items[idx as usize].trusted {
+                self.table.select(idx as usize);
+                return;
+            }
+        }
+    }
+
+    fn toggle_spacing(&mut self, app: &mut App) {
+        self.spacing = !self.spacing;
+        app.config.row_spacing = self.spacing;
+        if let Err(e) = app.config.store_keys(&["row_spacing"]) {
+            app.show_error(format!(
+                "Failed to update row spacing in config file:\n{}",
+                e
+            ));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Item> {
+        self.table
+            .state
+            .selected()
+            .and_then(|i| self.table.items.get(i))
+    }
+
+    fn run_action(&mut self, app: &mut App, action: EnterAction) {
+        let key = self.selected().map(seen::key);
+        match action {
+            EnterAction::Download => app.mode = Mode::Loading(LoadType::Downloading),
+            EnterAction::CopyMagnet => {
+                if let Some(item) = self.selected() {
+                    clipboard::copy(app, &item.magnet_link, "magnet link");
+                }
+            }
+            EnterAction::OpenBrowser => {
+                if let Some(link) = self.selected().map(|i| i.post_link.to_owned()) {
+                    match open::open(&link) {
+                        Ok(()) => app.notify(format!("Opened {}", link)),
+                        Err(e) => app.show_error(format!("Failed to open {}:\n{}", link, e)),
+                    }
+                }
+            }
+        }
+        // Downloads are marked once they've been sent instead
+        if let Some(key) = key.filter(|_| action != EnterAction::Download) {
+            self.mark_seen(app, key);
+        }
+    }
+
+    // Open the folder with the config in it, or copy its path when there's no desktop to open it in
+    fn open_config_dir(&mut self, app: &mut App) {
+        let dir = match config::config_dir() {
+            Ok(dir) => dir.to_string_lossy().to_string(),
+            Err(e) => {
+                app.show_error(format!("Failed to find config directory:\n{}", e));
+                return;
+            }
+        };
+        if open::has_desktop() && open::open(&dir).is_ok() {
+            app.notify(format!("Opened {}", dir));
+            return;
+        }
+        clipboard::copy(app, &dir, &dir);
+    }
+
+    // Remember a torrent was downloaded or opened, so it's marked when it shows up again
+    pub fn mark_seen(&mut self, app: &mut App, key: String) {
+        if self.seen.insert(key) {
+            if let Err(e) = seen::store(&self.seen) {
+                app.show_error(format!("Failed to save seen torrents:\n{}", e));
+            }
+        }
+    }
+
+    // For downloads made outside the app, or to unmark one
+    fn toggle_seen(&mut self, app: &mut App) {
+        let key = match self.selected().map(seen::key) {
+            Some(key) => key,
+            None => return,
+        };
+        let msg = match self.seen.remove(&key) {
+            true => "Marked as not seen",
+            false => {
+                self.seen.insert(key);
+                "Marked as seen"
+            }
+        };
+        match seen::store(&self.seen) {
+            Ok(()) => app.notify(msg),
+            Err(e) => app.show_error(format!("Failed to save seen torrents:\n{}", e)),
+        }
+    }
+
+    // One per line, which is what most clients' "add links" boxes expect
+    fn copy_magnets(&self, app: &mut App) {
+        let links = self
+            .table
+            .items
+            .iter()
+            .map(|i| i.magnet_link.to_owned())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<String>>();
+        if links.is_empty() {
+            app.notify("No magnet links to copy");
+            return;
+        }
+        let what = format!("{} magnet links", links.len());
+        clipboard::copy(app, &links.join("\n"), &what);
+    }
+
+    // Everything parsed for the selected item, for finding out why a value looks wrong
+    pub fn debug_info(&self, app: &App, e: &Event) -> Option<(String, Vec<String>)> {
+        if !app.config.debug {
+            return None;
+        }
+        if let Event::Key(KeyEvent {
+            code: KeyCode::F(12),
+            kind: KeyEventKind::Press,
+            ..
+        }) = e
+        {
+            let item = self
+                .table
+                .state
+                .selected()
+                .and_then(|i| self.table.items.get(i))?;
+            let mut lines = vec![
+                format!("source: {}", app.src.to_string()),
+                format!("index: {}", item.index),
+                format!("date: {}", item.date),
+                format!("timestamp: {}", item.timestamp),
+                format!("seeders: {}", item.seeders),
+                format!("leechers: {}", item.leechers),
+                format!("downloads: {}", item.downloads),
+                format!("comments: {}", item.comments),
+                format!("size: {}", item.size),
+                format!("bytes: {}", item.bytes),
+                format!("category: {} ({})", item.category, item.icon.label),
+                format!("trusted: {}", item.trusted),
+                format!("remake: {}", item.remake),
+                format!("info_hash: {}", item.info_hash),
+                format!("file_name: {}", item.file_name),
+                format!("torrent_link: {}", item.torrent_link),
+                format!("post_link: {}", item.post_link),
+                format!("magnet_link: {}", item.magnet_link),
+            ];
+            match item.extensions.is_empty() {
+                true => lines.push("No raw feed values, only RSS sources have them".to_owned()),
+                false => lines.extend(
+                    item.extensions
+                        .iter()
+                        .map(|(key, value)| format!("raw {}: {}", key, value)),
+                ),
+            }
+            return Some((item.title.to_owned(), lines));
+        }
+        None
+    }
+
+    // Width of each visible column inside the border, with the title taking whatever is left
+    fn column_widths(&self, app: &App, area: Rect) -> Vec<(Column, u16)> {
+        let columns = app.config.columns.visible();
+        let raw_date_width = self
+            .table
+            .items
+            .first()
+            .map(|i| i.date.width())
+            .unwrap_or(10) as u16;
+        let date_width = max(raw_date_width, 6);
+        // Room for the change in seeders after a refresh, like `+12`
+        let width = |c: &Column| match c {
+            Column::Seeders if !self.deltas.is_empty() => 9,
+            c => c.width(date_width),
+        };
+        let fixed_width: i32 = columns.iter().map(|c| width(c) as i32).sum();
+        // Two for the border, and one between each column
+        let mut title_width = max(
+            area.width as i32 - fixed_width - columns.len() as i32 - 1,
+            5,
+        ) as u16;
+        if app.config.max_title_width > 0 {
+            title_width = min(title_width, max(app.config.max_title_width, 5));
+        }
+        columns
+            .into_iter()
+            .map(|c| match c {
+                Column::Name => (c, title_width),
+                c => (c, width(&c)),
+            })
+            .collect()
+    }
+
+    fn display_title(&self, app: &App, item: &Item, width: u16) -> String {
+        let (_, symbol) = title_status(
+            item.trusted,
+            item.remake,
+            app.config.status_symbols,
+            app.config.prefer_remake_color,
+        );
+        let title = format!("{}{}", symbol, item.title);
+        match self.wrap {
+            true => wrap_title(&title, width as usize).join("\n"),
+            false => truncate_title(&title, width as usize),
+        }
+    }
+
+    // Rows that fit in `max_height`, scrolled the same way the table would to keep the
+    // selection on screen
+    fn visible_rows(
+        &self,
+        selected: usize,
+        max_height: u16,
+        height: impl Fn(&Item) -> u16,
+    ) -> Range<usize> {
+        let items = &self.table.items;
+        let (mut start, mut end, mut used) = (0, 0, 0);
+        while end < items.len() && used + height(&items[end]) <= max_height {
+            used += height(&items[end]);
+            end += 1;
+        }
+        if selected >= end && selected < items.len() {
+            // Scroll until the selection is the last row on screen
+            (start, end, used) = (selected, selected + 1, height(&items[selected]));
+            while start > 0 && used + height(&items[start - 1]) <= max_height {
+                start -= 1;
+                used += height(&items[start]);
+            }
+        }
+        start..end.max(start + 1).min(items.len())
+    }
+
+    // The sort for a left click on a header, if that column can be sorted by
+    pub fn clicked_sort(&self, app: &App, e: &Event) -> Option<Sort> {
+        let (column, row) = match e {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => (*column, *row),
+            _ => return None,
+        };
+        if row != self.area.y + 1 {
+            return None;
+        }
+        let mut x = self.area.x + 1;
+        for (c, width) in self.column_widths(app, self.area) {
+            if (x..x + width).contains(&column) {
+                return match c {
+                    Column::Date => Some(Sort::Date),
+                    Column::Size => Some(Sort::Size),
+                    Column::Seeders => Some(Sort::Seeders),
+                    Column::Leechers => Some(Sort::Leechers),
+                    Column::Downloads => Some(Sort::Downloads),
+                    Column::Ratio => Some(Sort::Ratio),
+                    _ => None,
+                };
+            }
+            x += width + 1;
+        }
+        None
     }
 }
 
@@ -38,8 +536,126 @@ impl Default for ResultsWidget {
     fn default() -> Self {
         ResultsWidget {
             table: StatefulTable::with_items(vec![]),
+            all_items: vec![],
+            min_seeders: 0,
+            dedup: false,
+            wrap: false,
+            spacing: false,
+            dim_remakes: false,
+            filters: vec![],
+            blocked: None,
+            trusted_view: TrustedView::All,
+            hidden: 0,
+            merged: 0,
+            skipped: 0,
+            count: None,
             sort: Sort::Date,
+            ascending: false,
+            area: Rect::default(),
+            seen: HashSet::new(),
+            deltas: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TitleStatus {
+    Normal,
+    Trusted,
+    Remake,
+}
+
+// Which color a title gets, and the symbol put before it. Symbols are shown alongside the
+// colors so status doesn't depend on color alone, and a torrent that's both trusted and a
+// remake always gets a symbol for whichever status its color doesn't show
+fn title_status(
+    trusted: bool,
+    remake: bool,
+    symbols: bool,
+    prefer_remake: bool,
+) -> (TitleStatus, &'static str) {
+    match (trusted, remake) {
+        (true, true) => match (symbols, prefer_remake) {
+            (true, false) => (TitleStatus::Trusted, "✓⚠ "),
+            (true, true) => (TitleStatus::Remake, "✓⚠ "),
+            (false, false) => (TitleStatus::Trusted, "⚠ "),
+            (false, true) => (TitleStatus::Remake, "✓ "),
+        },
+        (true, false) => (TitleStatus::Trusted, if symbols { "✓ " } else { "" }),
+        (false, true) => (TitleStatus::Remake, if symbols { "⚠ " } else { "" }),
+        (false, false) => (TitleStatus::Normal, ""),
+    }
+}
+
+// Greedily wrap a title on spaces, breaking words that don't fit on a line of their own
+fn wrap_title(title: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in title.split_whitespace() {
+        if !line.is_empty() {
+            match line.width() + 1 + word.width() > width {
+                true => lines.push(std::mem::take(&mut line)),
+                false => line.push(' '),
+            }
+        }
+        for c in word.chars() {
+            if !line.is_empty() && line.width() + c.width().unwrap_or(0) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// The release group in a title's leading brackets, like `SubsPlease` in `[SubsPlease] Frieren`
+pub fn release_group(title: &str) -> Option<&str> {
+    let rest = title.trim_start().strip_prefix('[')?;
+    let (group, _) = rest.split_once(']')?;
+    Some(group.trim()).filter(|g| !g.is_empty())
+}
+
+// Cut a title off to fit in `width` columns, ending with … so it's clear there's more. Wide
+// characters are kept whole
+fn truncate_title(title: &str, width: usize) -> String {
+    if title.width() <= width {
+        return title.to_owned();
+    }
+    let mut short = String::new();
+    for c in title.chars() {
+        if short.width() + c.width().unwrap_or(0) + 1 > width {
+            break;
         }
+        short.push(c);
+    }
+    format!("{}…", short.trim_end())
+}
+
+// Same units as the site, computed from the parsed size so every source looks alike
+fn format_bytes(bytes: usize) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < units.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, units[unit]),
+    }
+}
+
+fn format_ratio(item: &Item) -> String {
+    match (item.seeders, item.leechers) {
+        (0, 0) => "-".to_owned(),
+        (_, 0) => "∞".to_owned(),
+        _ if item.ratio() >= 1000.0 => "999+".to_owned(),
+        _ => format!("{:.2}", item.ratio()),
     }
 }
 
@@ -59,93 +675,188 @@ impl super::Widget for ResultsWidget {
             Mode::Normal => app.theme.border_focused_color,
             _ => app.theme.border_color,
         };
-        let raw_date_width = self.table.items.first().map(|i| i.date.len()).unwrap_or(10) as u16;
-        let date_width = max(raw_date_width, 6);
-        let title_width = max(area.width as i32 - 32 - date_width as i32, 5) as u16;
-        let binding = Constraint::from_lengths([3, title_width, 9, date_width, 4, 4, 5]);
-        let header_slice = &mut [
-            "Cat".to_owned(),
-            "Name".to_owned(),
-            format!("  {}", "Size"),
+        let widths = self.column_widths(app, area);
+        let columns: Vec<Column> = widths.iter().map(|(c, _)| *c).collect();
+        let title_width = widths
+            .iter()
+            .find(|(c, _)| *c == Column::Name)
+            .map(|(_, width)| *width)
+            .unwrap_or(5);
+        let binding = Constraint::from_lengths(widths.iter().map(|(_, width)| *width));
+        let raw_date_width = self
+            .table
+            .items
+            .first()
+            .map(|i| i.date.width())
+            .unwrap_or(10) as u16;
+        let date_header = |text: &str| {
             format!(
                 "{:^width$}",
-                "Date  ",
+                text,
                 width = max(raw_date_width, 4) as usize + 2
-            ),
-            format!(" {}", ""),
-            format!(" {}", ""),
-            format!(" {}", ""),
-        ];
+            )
+        };
         let direction = match app.ascending {
             true => "▲",
             false => "▼",
         };
-        let sort_idx = match self.sort {
-            Sort::Date => 3,
-            Sort::Size => 2,
-            Sort::Seeders => 4,
-            Sort::Leechers => 5,
-            Sort::Downloads => 6,
-        };
-        let sort_text = format!("{} {}", header_slice[sort_idx].trim(), direction);
-        let sort_fmt = match self.sort {
-            Sort::Size => format!("  {:<8}", sort_text),
-            Sort::Date => format!(
-                "{:^width$}",
-                sort_text,
-                width = max(raw_date_width, 4) as usize + 2
-            ),
-            Sort::Seeders => format!(" {:<3}", sort_text),
-            Sort::Leechers => format!(" {:<3}", sort_text),
-            Sort::Downloads => format!(" {:<3}", sort_text),
+        let sorted = match self.sort {
+            Sort::Date => Column::Date,
+            Sort::Size => Column::Size,
+            Sort::Seeders => Column::Seeders,
+            Sort::Leechers => Column::Leechers,
+            Sort::Downloads => Column::Downloads,
+            Sort::Ratio => Column::Ratio,
         };
-        header_slice[sort_idx] = sort_fmt;
-        let header = Row::new(header_slice.to_owned())
+        let header_slice: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let header = match c {
+                    Column::Category => "Cat".to_owned(),
+                    Column::Name => "Name".to_owned(),
+                    Column::Size => format!("  {}", "Size"),
+                    Column::Date => date_header("Date  "),
+                    Column::Seeders => format!(" {}", ""),
+                    Column::Leechers => format!(" {}", ""),
+                    Column::Downloads => format!(" {}", ""),
+                    Column::Comments => format!(" {}", ""),
+                    Column::Ratio => "Ratio".to_owned(),
+                    Column::InfoHash => "Info Hash".to_owned(),
+                };
+                if *c != sorted {
+                    return header;
+                }
+                let sort_text = format!("{} {}", header.trim(), direction);
+                match c {
+                    Column::Size => format!("  {:<8}", sort_text),
+                    Column::Date => date_header(&sort_text),
+                    Column::Ratio => sort_text,
+                    _ => format!(" {:<3}", sort_text),
+                }
+            })
+            .collect();
+        let header = Row::new(header_slice)
             .add_modifier(Modifier::BOLD)
             .add_modifier(Modifier::UNDERLINED)
             .fg(focus_color)
             .height(1)
             .bottom_margin(0);
 
+        let now = Local::now().timestamp();
+        let recent = |item: &Item| {
+            app.config.recent_hours > 0
+                && item.timestamp > 0
+                && now - item.timestamp < app.config.recent_hours as i64 * 3600
+        };
+
         Clear.render(area, buf);
-        let items: Vec<Row> = match app.mode {
-            Mode::Loading(_) => {
+        // Only build the rows that fit on screen, since pages can be large
+        let selected = self.table.state.selected().unwrap_or(0);
+        // Room taken by the borders and header
+        let chrome = match app.config.results_border {
+            true => 3,
+            false => 2,
+        };
+        let spacing = self.spacing as u16;
+        let visible = self.visible_rows(selected, area.height.saturating_sub(chrome), |item| {
+            self.display_title(app, item, title_width)
+                .lines()
+                .count()
+                .max(1) as u16
+                + spacing
+        });
+        let items: Vec<Row> = match app.loading {
+            true => {
                 let area = centered_rect(8, 1, size);
                 Paragraph::new("Loading…").render(area, buf);
                 vec![]
             }
-            _ => self
-                .table
-                .items
+            false => self.table.items[visible.to_owned()]
                 .iter()
                 .map(|item| {
-                    Row::new(vec![
-                        Text::styled(item.icon.label, Style::new().fg(item.icon.color)),
-                        Text::styled(
-                            item.title.to_owned(),
-                            Style::new().fg(if item.trusted {
-                                app.theme.trusted
-                            } else if item.remake {
-                                app.theme.remake
-                            } else {
-                                app.theme.fg
-                            }),
+                    let title = self.display_title(app, item, title_width);
+                    let height = title.lines().count().max(1) as u16;
+                    let row = Row::new(columns.iter().map(|c| match c {
+                        Column::Category => Text::styled(
+                            item.icon.label,
+                            Style::new().fg(app.theme.category_color(item.category)),
                         ),
-                        Text::raw(format!("{:>9}", item.size)),
-                        Text::raw(format!("{:<14}", item.date)),
-                        Text::styled(
-                            format!("{:>4}", item.seeders),
-                            Style::new().fg(app.theme.trusted),
+                        Column::Name => Text::styled(
+                            title.to_owned(),
+                            Style::new().fg(
+                                match title_status(
+                                    item.trusted,
+                                    item.remake,
+                                    app.config.status_symbols,
+                                    app.config.prefer_remake_color,
+                                ) {
+                                    (TitleStatus::Trusted, _) => app.theme.trusted,
+                                    (TitleStatus::Remake, _) => app.theme.remake,
+                                    (TitleStatus::Normal, _) => app.theme.fg,
+                                },
+                            ),
                         ),
-                        Text::styled(
+                        Column::Size => Text::raw(format!(
+                            "{:>9}",
+                            match app.config.normalize_sizes {
+                                true => format_bytes(item.bytes),
+                                false => item.size.to_owned(),
+                            }
+                        )),
+                        Column::Date => match recent(item) {
+                            true => Text::styled(
+                                format!("{:<14}", item.date),
+                                Style::new().fg(app.theme.recent).bold(),
+                            ),
+                            false => Text::raw(format!("{:<14}", item.date)),
+                        },
+                        Column::Seeders => {
+                            let seeders = Span::styled(
+                                format!("{:>4}", item.seeders),
+                                Style::new().fg(app.theme.trusted),
+                            );
+                            match self.deltas.get(&seen::key(item)) {
+                                Some(d) => Text::from(Line::from(vec![
+                                    seeders,
+                                    Span::styled(
+                                        format!(" {:+}", d),
+                                        Style::new().fg(match *d > 0 {
+                                            true => app.theme.trusted,
+                                            false => app.theme.remake,
+                                        }),
+                                    ),
+                                ])),
+                                None => Text::from(seeders),
+                            }
+                        }
+                        Column::Leechers => Text::styled(
                             format!("{:>4}", item.leechers),
                             Style::new().fg(app.theme.remake),
                         ),
-                        Text::raw(shorten_number(item.downloads)),
-                    ])
+                        Column::Downloads => Text::raw(shorten_number(item.downloads)),
+                        Column::Comments => Text::raw(format!("{:>4}", item.comments)),
+                        Column::Ratio => Text::raw(format!("{:>7}", format_ratio(item))),
+                        Column::InfoHash => Text::raw(item.info_hash.to_owned()),
+                    }))
                     .fg(app.theme.fg)
-                    .height(1)
-                    .bottom_margin(0)
+                    .height(height)
+                    .bottom_margin(spacing);
+                    let row = match (self.trusted_view, item.trusted) {
+                        (TrustedView::Dim, false) => row.add_modifier(Modifier::DIM),
+                        _ => row,
+                    };
+                    let row = match self.dim_remakes && item.remake {
+                        true => row
+                            .add_modifier(Modifier::DIM)
+                            .add_modifier(Modifier::CROSSED_OUT),
+                        false => row,
+                    };
+                    match app.config.mark_seen && self.seen.contains(&seen::key(item)) {
+                        true => row
+                            .add_modifier(Modifier::DIM)
+                            .add_modifier(Modifier::ITALIC),
+                        false => row,
+                    }
                 })
                 .collect(),
         };
@@ -160,42 +871,117 @@ impl super::Widget for ResultsWidget {
         });
 
         let num_items = items.len();
-        let first_item = (app.page - 1) * 75;
+        let first_item = (app.page - 1) * app.src.page_size();
+        let hidden = match self.hidden {
+            0 => "".to_owned(),
+            n => format!(", {} hidden", n),
+        };
+        let merged = match self.merged {
+            0 => "".to_owned(),
+            n => format!(", {} duplicates merged", n),
+        };
+        let skipped = match self.skipped {
+            0 => "".to_owned(),
+            n => format!(", {} skipped", n),
+        };
         let table = Table::new(items, [Constraint::Percentage(100)])
             .header(header)
             .block(
-                border_block(app.theme, app.mode == Mode::Normal).title(format!(
-                    "Results {}-{} ({} total): Page {}/{}",
+                match app.config.results_border {
+                    true => border_block(app, app.mode == Mode::Normal),
+                    false => border_block(app, app.mode == Mode::Normal).borders(Borders::TOP),
+                }
+                .title(format!(
+                    "Results {}-{} ({} total{}{}{}): Page {}/{}",
                     first_item + 1,
-                    num_items + first_item,
+                    self.all_items.len() + first_item,
                     app.total_results,
+                    hidden,
+                    merged,
+                    skipped,
                     app.page,
                     app.last_page
                 )),
             )
-            .highlight_style(Style::default().bg(app.theme.hl_bg))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg))
             .widths(&binding);
-        StatefulWidget::render(table, area, buf, &mut self.table.state.to_owned());
+        let mut state = TableState::default().with_selected(
+            self.table
+                .state
+                .selected()
+                .map(|i| i.saturating_sub(visible.start)),
+        );
+        StatefulWidget::render(table, area, buf, &mut state);
         StatefulWidget::render(sb, sb_area, buf, &mut self.table.scrollbar_state.to_owned());
 
         let source_str = format!("Source: {}", app.src.to_string());
         let text = Paragraph::new(source_str.clone());
-        let right = Rect::new(
-            area.right() - 1 - source_str.len() as u16,
-            area.top(),
-            source_str.len() as u16,
-            1,
-        );
+        let width = min(source_str.width() as u16, area.width.saturating_sub(2));
+        let right = Rect::new(area.right().saturating_sub(1 + width), area.top(), width, 1);
         f.render_widget(text, right);
 
-        match app.mode {
-            Mode::Loading(_) => {}
-            _ => {
-                if num_items == 0 {
-                    let center = centered_rect(10, 1, f.size());
-                    f.render_widget(Paragraph::new("No results"), center);
+        if let Some(count) = self.count {
+            let count = format!("{}", count);
+            let left = Rect::new(
+                area.left() + 1,
+                area.bottom().saturating_sub(1),
+                count.width() as u16,
+                1,
+            );
+            f.render_widget(Paragraph::new(count).fg(app.theme.fg), left);
+        }
+
+        let progress = app
+            .downloading
+            .map(|(done, total)| format!("Downloading {}/{}", done, total));
+        let refreshed = app
+            .refreshed
+            .map(|time| format!("Refreshed {}", time.format("%H:%M:%S")));
+        let notice = app
+            .notice
+            .as_ref()
+            .filter(|(_, shown)| shown.elapsed() < Duration::from_secs(3))
+            .map(|(msg, _)| msg.to_owned());
+        if let Some(info) = progress.or(notice).or(refreshed) {
+            let text = Paragraph::new(info.clone()).fg(app.theme.border_color);
+            // Long notices are cut off rather than drawn past the border
+            let width = min(info.width() as u16, area.width.saturating_sub(2));
+            let right = Rect::new(
+                area.right().saturating_sub(1 + width),
+                area.bottom().saturating_sub(1),
+                width,
+                1,
+            );
+            f.render_widget(text, right);
+        }
+
+        // Only shown once a load has finished, so an empty search is distinguishable
+        if !app.loading && num_items == 0 {
+            let mut filters = self.filters.to_owned();
+            if self.min_seeders > 0 {
+                filters.push(format!("At least {} seeders", self.min_seeders));
+            }
+            if self.trusted_view == TrustedView::Only {
+                filters.push("Only trusted".to_owned());
+            }
+            let mut lines = vec![match (self.blocked, self.hidden) {
+                (Some(reason), _) => Line::from(reason),
+                (None, 0) => Line::from("No results found"),
+                (None, n) => Line::from(format!("All {} results are hidden", n)),
+            }];
+            match filters.is_empty() {
+                true => lines.push(Line::from("Press / to search or r to reload")),
+                false => {
+                    lines.push(Line::from(format!(
+                        "Active filters: {}",
+                        filters.join(", ")
+                    )));
+                    lines.push(Line::from("Press F to clear them"));
                 }
             }
+            let text = Text::from(lines);
+            let center = centered_rect(text.width() as u16, text.height() as u16, area);
+            f.render_widget(Paragraph::new(text).alignment(Alignment::Center), center);
         }
     }
 
@@ -208,6 +994,21 @@ impl super::Widget for ResultsWidget {
         }) = e
         {
             use KeyCode::*;
+            // Vim-style count prefix, e.g. `5j` or `10G`
+            if let (Char(c @ '0'..='9'), &KeyModifiers::NONE) = (code, modifiers) {
+                if *c != '0' || self.count.is_some() {
+                    let digit = c.to_digit(10).unwrap_or(0) as usize;
+                    self.count = Some(
+                        self.count
+                            .unwrap_or(0)
+                            .saturating_mul(10)
+                            .saturating_add(digit),
+                    );
+                    return;
+                }
+            }
+            let count = self.count.take();
+            let times = count.unwrap_or(1) as isize;
             match (code, modifiers) {
                 (Char('c'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Category;
@@ -243,28 +1044,75 @@ impl super::Widget for ResultsWidget {
                     }
                 }
                 (Char('r'), &KeyModifiers::NONE) => {
-                    app.mode = Mode::Loading(LoadType::Searching);
+                    app.mode = Mode::Loading(LoadType::Reloading);
+                }
+                (Char('r'), &KeyModifiers::CONTROL) => {
+                    app.mode = Mode::Loading(LoadType::Resetting);
+                }
+                (Char('z'), &KeyModifiers::NONE) => {
+                    self.toggle_min_seeders(app);
+                }
+                (Char('T'), &KeyModifiers::SHIFT) => {
+                    self.toggle_trusted();
+                }
+                (Char('F'), &KeyModifiers::SHIFT) => {
+                    self.min_seeders = 0;
+                    self.trusted_view = TrustedView::All;
+                    self.update_view(self.selected_link());
+                    if !self.filters.is_empty() {
+                        app.mode = Mode::Loading(LoadType::ClearingFilters);
+                    }
+                }
+                (Char('y'), &KeyModifiers::NONE) => {
+                    if let Some(item) = self.selected() {
+                        clipboard::copy(app, &item.title, "title");
+                    }
+                }
+                (Char('Y'), &KeyModifiers::SHIFT) => {
+                    self.copy_magnets(app);
+                }
+                (Char('R'), &KeyModifiers::SHIFT) => {
+                    self.dim_remakes = !self.dim_remakes;
+                }
+                (Char('w'), &KeyModifiers::NONE) => {
+                    self.wrap = !self.wrap;
+                }
+                (Char('e'), &KeyModifiers::NONE) => {
+                    self.toggle_spacing(app);
                 }
                 (Char('q'), &KeyModifiers::NONE) => {
-                    app.quit();
+                    app.request_quit();
                 }
                 (Char('j') | KeyCode::Down, &KeyModifiers::NONE) => {
-                    self.table.next(1);
+                    self.table
+                        .next_nav(times, app.config.wrap_navigation && count.is_none());
                 }
                 (Char('k') | KeyCode::Up, &KeyModifiers::NONE) => {
-                    self.table.next(-1);
+                    self.table
+                        .next_nav(-times, app.config.wrap_navigation && count.is_none());
+                }
+                (Char('u'), &KeyModifiers::NONE) => {
+                    self.next_trusted(1, app.config.wrap_navigation);
+                }
+                (Char('U'), &KeyModifiers::SHIFT) => {
+                    self.next_trusted(-1, app.config.wrap_navigation);
                 }
                 (Char('J'), &KeyModifiers::SHIFT) => {
-                    self.table.next(4);
+                    self.table.next_clamp(4 * times);
                 }
                 (Char('K'), &KeyModifiers::SHIFT) => {
-                    self.table.next(-4);
+                    self.table.next_clamp(-4 * times);
                 }
+                // With a count, jump to that row instead
                 (Char('G'), &KeyModifiers::SHIFT) => {
-                    self.table.select(max(self.table.items.len(), 1) - 1);
+                    let last = max(self.table.items.len(), 1) - 1;
+                    self.table
+                        .select(count.map(|c| c.max(1) - 1).unwrap_or(last).min(last));
                 }
                 (Char('g'), &KeyModifiers::NONE) => {
-                    self.table.select(0);
+                    let last = max(self.table.items.len(), 1) - 1;
+                    self.table
+                        .select(count.map(|c| c.max(1) - 1).unwrap_or(0).min(last));
                 }
                 (Char('H') | Char('P'), &KeyModifiers::SHIFT) => {
                     if app.page != 1 {
@@ -279,7 +1127,30 @@ impl super::Widget for ResultsWidget {
                     }
                 }
                 (Enter, &KeyModifiers::NONE) => {
-                    app.mode = Mode::Loading(LoadType::Downloading);
+                    self.run_action(app, app.config.enter_action);
+                }
+                (Char('D'), &KeyModifiers::SHIFT) => {
+                    self.run_action(app, EnterAction::Download);
+                }
+                (Char('M'), &KeyModifiers::SHIFT) => {
+                    self.toggle_seen(app);
+                }
+                (Char('m'), &KeyModifiers::NONE) => {
+                    self.run_action(app, EnterAction::CopyMagnet);
+                }
+                (Char('o'), &KeyModifiers::NONE) => {
+                    self.run_action(app, EnterAction::OpenBrowser);
+                }
+                (Char('O'), &KeyModifiers::SHIFT) => {
+                    self.open_config_dir(app);
+                }
+                (Char('A'), &KeyModifiers::SHIFT) => {
+                    if !self.table.items.is_empty() {
+                        app.mode = Mode::Confirm(Confirm::DownloadAll(self.table.items.len()));
+                    }
+                }
+                (Char('v'), &KeyModifiers::NONE) => {
+                    app.mode = Mode::Loading(LoadType::Files);
                 }
                 (Char('s'), &KeyModifiers::CONTROL) => {
                     app.mode = Mode::Sources;
@@ -287,6 +1158,9 @@ impl super::Widget for ResultsWidget {
                 (Char('d'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Clients;
                 }
+                (Char(','), &KeyModifiers::NONE) => {
+                    app.mode = Mode::Settings;
+                }
                 _ => {}
             }
         }
@@ -294,26 +1168,114 @@ impl super::Widget for ResultsWidget {
 
     fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
         Some(vec![
-            ("Enter", "Confirm"),
+            ("Enter", "Run enter_action"),
+            ("D", "Download"),
+            ("m", "Copy magnet link"),
+            ("M", "Toggle marked as seen"),
+            ("o", "Open in browser"),
+            ("O", "Open config directory"),
+            ("Ctrl-g", "Search release group"),
             ("q", "Exit App"),
             ("g", "Top"),
             ("G", "Bottom"),
             ("j, ↓", "Down"),
             ("k, ↑", "Up"),
+            ("u", "Next trusted"),
+            ("U", "Prev trusted"),
             ("n, l, →", "Next Page"),
             ("p, h, ←", "Prev Page"),
             ("N, L", "Last Page"),
             ("P, H", "First Page"),
-            ("r", "Reload"),
+            ("r", "Reload (skips cache)"),
+            ("Esc", "Cancel loading"),
+            ("Ctrl-r", "Reset to defaults"),
+            ("v", "View files"),
+            ("A", "Download all shown"),
+            ("z", "Toggle hiding low seeders"),
+            ("T", "Show all, dim or only trusted"),
+            ("F", "Clear all filters"),
+            ("R", "Toggle dimming remakes"),
+            ("w", "Toggle wrapping titles"),
+            ("e", "Toggle row spacing"),
+            ("y", "Copy title"),
+            ("Y", "Copy all magnet links"),
+            ("Ctrl-y", "Copy search url"),
+            ("x", "Toggle excluded terms"),
             ("/, i", "Search"),
             ("c", "Categories"),
+            ("[, ]", "Prev/next category group"),
             ("f", "Filters"),
             ("s", "Sort"),
             ("S", "Sort reversed"),
+            ("b", "Sort by most seeders"),
             ("t", "Themes"),
+            ("Ctrl-t", "Next theme"),
             ("d", "Select download client"),
             ("Ctrl-p", "Goto page"),
             ("Ctrl-s", "Select source"),
+            (",", "Settings"),
         ])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{release_group, title_status, truncate_title, TitleStatus};
+
+    #[test]
+    fn trusted_remake_keeps_both() {
+        assert_eq!(
+            title_status(true, true, false, false),
+            (TitleStatus::Trusted, "⚠ ")
+        );
+        assert_eq!(
+            title_status(true, true, false, true),
+            (TitleStatus::Remake, "✓ ")
+        );
+        assert_eq!(
+            title_status(true, true, true, false),
+            (TitleStatus::Trusted, "✓⚠ ")
+        );
+        assert_eq!(
+            title_status(true, true, true, true),
+            (TitleStatus::Remake, "✓⚠ ")
+        );
+    }
+
+    #[test]
+    fn single_status() {
+        assert_eq!(
+            title_status(true, false, false, true),
+            (TitleStatus::Trusted, "")
+        );
+        assert_eq!(
+            title_status(false, true, true, false),
+            (TitleStatus::Remake, "⚠ ")
+        );
+        assert_eq!(
+            title_status(false, false, true, true),
+            (TitleStatus::Normal, "")
+        );
+    }
+
+    #[test]
+    fn finds_release_group() {
+        assert_eq!(
+            release_group("[SubsPlease] Sousou no Frieren - 26 (1080p)"),
+            Some("SubsPlease")
+        );
+        assert_eq!(release_group(" [ Erai-raws ] Frieren"), Some("Erai-raws"));
+        assert_eq!(release_group("Frieren [1080p]"), None);
+        assert_eq!(release_group("[] Frieren"), None);
+        assert_eq!(release_group("[Unclosed Frieren"), None);
+    }
+
+    #[test]
+    fn truncates_titles() {
+        assert_eq!(truncate_title("Frieren 01", 10), "Frieren 01");
+        assert_eq!(truncate_title("Frieren 01 1080p", 10), "Frieren 0…");
+        // Each of these is two columns wide, so only four fit before the …
+        assert_eq!(truncate_title("葬送のフリーレン", 9), "葬送のフ…");
+        assert_eq!(truncate_title("葬送のフリーレン", 10), "葬送のフ…");
+    }
+}
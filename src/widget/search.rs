@@ -1,27 +1,182 @@
+use std::{
+    cmp::min,
+    time::{Duration, Instant},
+};
+
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
-    layout::{Margin, Rect},
+    layout::Rect,
     style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Clear, Paragraph, Widget},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr as _;
 
-use crate::app::{App, LoadType, Mode};
+use crate::{
+    app::{App, LoadType, Mode},
+    source::Item,
+};
 
 use super::{
     border_block,
     input::{self, InputWidget},
+    results::release_group,
 };
 
+// What to do when there's nothing to search for, which makes nyaa list every torrent
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EmptySearch {
+    All,      // Browse everything
+    Category, // Only browse when a category is selected
+    Block,    // Never browse
+}
+
+impl EmptySearch {
+    // The reason an empty search isn't sent, if it's blocked
+    pub fn blocked(&self, query: &str, category: usize) -> Option<&'static str> {
+        // Only excluding terms still lists everything else
+        let empty = query.split_whitespace().all(|w| w.starts_with('-'));
+        match (self, empty, category) {
+            (_, false, _) | (EmptySearch::All, _, _) | (EmptySearch::Category, _, 1..) => None,
+            (EmptySearch::Category, _, 0) => Some("Empty searches need a category, press c"),
+            (EmptySearch::Block, _, _) => Some("Empty searches are turned off"),
+        }
+    }
+}
+
 pub struct SearchWidget {
     pub input: InputWidget,
+    pub deadline: Option<Instant>,       // When to run a live search
+    pub exclude: bool,                   // Whether the excluded terms from the config are used
+    pub exact: bool,                     // Quote the search so nyaa only matches the whole phrase
+    pub count_deadline: Option<Instant>, // When to count the results of what's typed so far
+    pub count: Option<(String, usize)>,  // Query that was counted and how many results it had
 }
 
 impl Default for SearchWidget {
     fn default() -> Self {
         SearchWidget {
             input: InputWidget::new(300, Some(|_| true)),
+            deadline: None,
+            exclude: true,
+            exact: false,
+            count_deadline: None,
+            count: None,
+        }
+    }
+}
+
+impl SearchWidget {
+    // Typing the quotes by hand counts too
+    fn is_exact(&self) -> bool {
+        let input = self.input.input.trim();
+        self.exact || (input.len() > 1 && input.starts_with('"') && input.ends_with('"'))
+    }
+
+    // The count is only shown while it's still for what's typed
+    fn title(&self, app: &App) -> String {
+        let mut title = match self.is_exact() {
+            true => "Search (exact match)".to_owned(),
+            false => "Search".to_owned(),
+        };
+        if let Some((query, count)) = &self.count {
+            if app.mode == Mode::Search && *query == self.query(app) {
+                title = format!("{}: {} results", title, count);
+            }
+        }
+        title
+    }
+
+    // Expands a leading !alias from the config, leaving unknown aliases as typed, quotes it for
+    // an exact match, then adds the excluded terms from the config
+    pub fn query(&self, app: &App) -> String {
+        let input = self.input.input.trim_start();
+        let (first, rest) = input.split_once(' ').unwrap_or((input, ""));
+        let mut query = match first
+            .strip_prefix('!')
+            .and_then(|name| app.config.aliases.get(name))
+        {
+            Some(expanded) if rest.is_empty() => expanded.to_owned(),
+            Some(expanded) => format!("{} {}", expanded, rest),
+            None => self.input.input.to_owned(),
+        };
+        let trimmed = query.trim();
+        if self.exact
+            && !trimmed.is_empty()
+            && !(trimmed.starts_with('"') && trimmed.ends_with('"'))
+        {
+            query = format!("\"{}\"", trimmed.replace('"', ""));
+        }
+        if self.exclude {
+            for term in app.config.exclude.iter().map(|t| t.trim_start_matches('-')) {
+                let term = format!("-{}", term);
+                if term.len() > 1 && !query.split_whitespace().any(|w| w == term) {
+                    query = format!("{} {}", query, term).trim_start().to_owned();
+                }
+            }
+        }
+        query
+    }
+
+    // Restart the debounce on every edit, running a live search or only counting the results
+    fn debounce(&mut self, app: &App) {
+        let at = Instant::now() + Duration::from_millis(app.config.live_search_delay);
+        if app.config.live_search {
+            self.deadline = Some(at);
+        } else if app.config.search_count {
+            self.count_deadline = Some(at);
+        }
+    }
+
+    // Add the selected torrent's release group to the search, to find everything else by them
+    pub fn search_group(&mut self, app: &mut App, e: &Event, item: Option<&Item>) {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('g'),
+            kind: KeyEventKind::Press,
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) = e
+        {
+            let group = match item.and_then(|i| release_group(&i.title)) {
+                Some(group) => format!("[{}]", group),
+                None => {
+                    app.notify("No release group in title");
+                    return;
+                }
+            };
+            let input = self.input.input.trim();
+            if !input
+                .split_whitespace()
+                .any(|w| w.eq_ignore_ascii_case(&group))
+            {
+                self.input.input = format!("{} {}", group, input).trim_end().to_owned();
+                self.input.cursor = self.input.input.len();
+            }
+            app.page = 1;
+            app.mode = Mode::Loading(LoadType::Searching);
+        }
+    }
+
+    pub fn toggle_exclude(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('x'),
+            kind: KeyEventKind::Press,
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) = e
+        {
+            if app.config.exclude.is_empty() {
+                app.notify("No excluded terms in config");
+                return;
+            }
+            self.exclude = !self.exclude;
+            app.notify(match self.exclude {
+                true => format!("Excluding {}", app.config.exclude.join(", ")),
+                false => "Not excluding terms".to_owned(),
+            });
+            app.mode = Mode::Loading(LoadType::Searching);
         }
     }
 }
@@ -29,13 +184,37 @@ impl Default for SearchWidget {
 impl super::Widget for SearchWidget {
     fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
         let buf = f.buffer_mut();
-        let block = border_block(app.theme, app.mode == Mode::Search).title("Search");
+        // Only the title and input on a single line, leaving the rest to the results
+        if app.config.dense {
+            let prefix = format!("{}: ", self.title(app));
+            let width = min(prefix.width() as u16, area.width);
+            let color = match app.mode == Mode::Search {
+                true => app.theme.border_focused_color,
+                false => app.theme.border_color,
+            };
+            Clear.render(area, buf);
+            Block::new()
+                .bg(app.theme.bg)
+                .fg(app.theme.fg)
+                .render(area, buf);
+            Paragraph::new(prefix)
+                .fg(color)
+                .render(Rect::new(area.x, area.y, width, 1), buf);
+            let input_area = Rect::new(area.x + width, area.y, area.width - width, 1);
+            self.input.draw(f, app, input_area);
+            if app.mode == Mode::Search {
+                self.input.show_cursor(f, input_area);
+            }
+            return;
+        }
+        let block = match app.config.search_border {
+            true => border_block(app, app.mode == Mode::Search),
+            false => border_block(app, app.mode == Mode::Search).borders(Borders::TOP),
+        }
+        .title(self.title(app));
         Clear.render(area, buf);
+        let input_area = block.inner(area);
         block.render(area, buf);
-        let input_area = area.inner(&Margin {
-            vertical: 1,
-            horizontal: 1,
-        });
 
         let text = Paragraph::new(Line::from(vec![
             Span::raw("Press "),
@@ -44,7 +223,7 @@ impl super::Widget for SearchWidget {
             Span::styled("?", Style::new().bold()),
             Span::raw(" for help"),
         ]));
-        let right = Rect::new(area.right() - 23, area.top(), 23, 1);
+        let right = Rect::new(area.right().saturating_sub(23), area.top(), 23, 1);
         text.render(right, buf);
 
         self.input.draw(f, app, input_area);
@@ -65,20 +244,36 @@ impl super::Widget for SearchWidget {
             match (code, modifiers) {
                 (Esc, &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
+                    self.deadline = None;
+                    self.count_deadline = None;
                 }
                 (Enter, &KeyModifiers::NONE) => {
                     app.mode = Mode::Loading(LoadType::Searching);
                     app.page = 1; // Go back to first page
+                    self.deadline = None;
+                    self.count_deadline = None;
+                }
+                (Char('q'), &KeyModifiers::CONTROL) => {
+                    self.exact = !self.exact;
+                    self.debounce(app);
                 }
                 _ => {
+                    let prev = self.input.input.to_owned();
                     self.input.handle_event(app, evt);
+                    if self.input.input != prev {
+                        self.debounce(app);
+                    }
                 }
             };
         }
     }
 
     fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        let mut search_help = vec![("Enter", "Confirm"), ("Esc", "Stop")];
+        let mut search_help = vec![
+            ("Enter", "Confirm"),
+            ("Esc", "Stop"),
+            ("Ctrl-q", "Toggle exact match"),
+        ];
         if let Some(input_help) = input::InputWidget::get_help() {
             search_help.extend(input_help);
         }
@@ -0,0 +1,294 @@
+use std::cmp::min;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Style,
+    widgets::{Row, StatefulWidget as _, Table},
+    Frame,
+};
+
+use crate::{
+    app::{App, Mode},
+    client::Client,
+    config::Config,
+};
+
+use super::{
+    border_block,
+    category::{self, ALL_CATEGORIES},
+    filter::Filter,
+    results::ColumnsConfig,
+    sort::Sort,
+    theme::{self, THEMES},
+    EnumIter, StatefulTable, Widget,
+};
+
+#[derive(Clone, Copy)]
+pub enum Setting {
+    Category,
+    Filter,
+    Sort,
+    Theme,
+    Client,
+    Column(&'static str),
+}
+
+static SETTINGS: &[Setting] = &[
+    Setting::Category,
+    Setting::Filter,
+    Setting::Sort,
+    Setting::Theme,
+    Setting::Client,
+    Setting::Column("category"),
+    Setting::Column("size"),
+    Setting::Column("date"),
+    Setting::Column("seeders"),
+    Setting::Column("leechers"),
+    Setting::Column("downloads"),
+    Setting::Column("comments"),
+    Setting::Column("ratio"),
+    Setting::Column("info_hash"),
+];
+
+impl Setting {
+    fn name(&self) -> String {
+        match self {
+            Setting::Category => "Default category".to_owned(),
+            Setting::Filter => "Default filter".to_owned(),
+            Setting::Sort => "Default sort".to_owned(),
+            Setting::Theme => "Theme".to_owned(),
+            Setting::Client => "Download client".to_owned(),
+            Setting::Column(name) => format!("Show {} column", name.replace('_', " ")),
+        }
+    }
+
+    // Where the setting is stored in the config file
+    fn key(&self) -> String {
+        match self {
+            Setting::Category => "default_category".to_owned(),
+            Setting::Filter => "default_filter".to_owned(),
+            Setting::Sort => "default_sort".to_owned(),
+            Setting::Theme => "default_theme".to_owned(),
+            Setting::Client => "client.default".to_owned(),
+            Setting::Column(name) => format!("columns.{}", name),
+        }
+    }
+
+    fn value(&self, config: &Config) -> String {
+        match self {
+            Setting::Category => config.default_category.to_owned(),
+            Setting::Filter => config.default_filter.to_string(),
+            Setting::Sort => config.default_sort.to_string(),
+            Setting::Theme => config.default_theme.to_owned(),
+            Setting::Client => config.client.default.to_string(),
+            Setting::Column(name) => {
+                let mut columns = config.columns.clone();
+                match column(&mut columns, name) {
+                    true => "Yes".to_owned(),
+                    false => "No".to_owned(),
+                }
+            }
+        }
+    }
+
+    // Move to the next or previous value, wrapping around
+    fn change(&self, config: &mut Config, dir: isize) {
+        match self {
+            Setting::Category => {
+                let entries: Vec<&str> = ALL_CATEGORIES
+                    .iter()
+                    .flat_map(|cat| cat.entries.iter().map(|ent| ent.cfg))
+                    .collect();
+                let i = entries
+                    .iter()
+                    .position(|cfg| cfg.eq_ignore_ascii_case(&config.default_category))
+                    .unwrap_or(0);
+                config.default_category = cycle(&entries, i, dir).to_owned();
+            }
+            Setting::Filter => {
+                let i = config.default_filter.clone() as usize;
+                config.default_filter = cycle(Filter::iter().as_slice(), i, dir);
+            }
+            Setting::Sort => {
+                let i = config.default_sort.clone() as usize;
+                config.default_sort = cycle(Sort::iter().as_slice(), i, dir);
+            }
+            Setting::Theme => {
+                let i = theme::find_theme(config.default_theme.to_owned())
+                    .map(|(i, _)| i)
+                    .unwrap_or(0);
+                config.default_theme = cycle(THEMES, i, dir).name.to_owned();
+            }
+            Setting::Client => {
+                let i = config.client.default as usize;
+                config.client.default = cycle(Client::iter().as_slice(), i, dir);
+            }
+            Setting::Column(name) => {
+                let show = column(&mut config.columns, name);
+                *show = !*show;
+            }
+        }
+    }
+}
+
+fn cycle<T: Clone>(items: &[T], current: usize, dir: isize) -> T {
+    let len = items.len() as isize;
+    items[(current as isize + dir).rem_euclid(len) as usize].clone()
+}
+
+fn column<'a>(columns: &'a mut ColumnsConfig, name: &str) -> &'a mut bool {
+    match name {
+        "category" => &mut columns.category,
+        "size" => &mut columns.size,
+        "date" => &mut columns.date,
+        "seeders" => &mut columns.seeders,
+        "leechers" => &mut columns.leechers,
+        "downloads" => &mut columns.downloads,
+        "comments" => &mut columns.comments,
+        "ratio" => &mut columns.ratio,
+        _ => &mut columns.info_hash,
+    }
+}
+
+pub struct SettingsPopup {
+    pub table: StatefulTable<Setting>,
+    pub draft: Config, // Edited here, and only stored on save
+}
+
+impl SettingsPopup {
+    pub fn edit(&mut self, config: &Config) {
+        self.draft = config.to_owned();
+    }
+
+    fn save(&mut self, app: &mut App) {
+        if category::find_category(self.draft.default_category.to_owned()).is_none() {
+            app.show_error(format!(
+                "Unknown category \"{}\"",
+                self.draft.default_category
+            ));
+            return;
+        }
+        let theme = match theme::find_theme(self.draft.default_theme.to_owned()) {
+            Some((_, theme)) => theme,
+            None => {
+                app.show_error(format!("Unknown theme \"{}\"", self.draft.default_theme));
+                return;
+            }
+        };
+        // The client's own settings are filled in and checked the same way as when selecting it
+        let prev = std::mem::replace(&mut app.config, self.draft.to_owned());
+        let client = app.config.client.default;
+        let mut keys: Vec<String> = SETTINGS.iter().map(|s| s.key()).collect();
+        if !client.has_config(&app.config.client) {
+            keys.push(format!("client.{}", client.to_string()));
+        }
+        if let Err(e) = client.load_config(app) {
+            app.config = prev;
+            app.show_error(format!("Settings weren't saved:\n{}", e));
+            return;
+        }
+        if let Err(e) = app.config.store_keys(&keys) {
+            app.show_error(format!("Failed to save settings to config file:\n{}", e));
+            return;
+        }
+        app.theme = theme;
+        app.client = client;
+        app.notify("Saved settings");
+        app.mode = Mode::Normal;
+    }
+}
+
+impl Default for SettingsPopup {
+    fn default() -> Self {
+        SettingsPopup {
+            table: StatefulTable::with_items(SETTINGS.to_vec()),
+            draft: Config::default(),
+        }
+    }
+}
+
+impl Widget for SettingsPopup {
+    fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
+        let buf = f.buffer_mut();
+        let height = min(self.table.items.len() as u16 + 2, area.height);
+        let center = super::centered_rect(50, height, area);
+        let clear = super::centered_rect(center.width + 2, center.height, area);
+        let items = self.table.items.iter().map(|setting| {
+            Row::new(vec![
+                format!(" {}", setting.name()),
+                setting.value(&self.draft),
+            ])
+        });
+        super::clear(clear, buf, app.theme.bg);
+        let table = Table::new(
+            items,
+            [Constraint::Percentage(60), Constraint::Percentage(40)],
+        )
+        .block(border_block(app, true).title("Settings: s to save, Esc to cancel"))
+        .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
+        table.render(center, buf, &mut self.table.state.to_owned());
+    }
+
+    fn handle_event(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            use KeyCode::*;
+            let selected = self
+                .table
+                .state
+                .selected()
+                .and_then(|i| self.table.items.get(i))
+                .copied();
+            match (code, modifiers) {
+                (Esc | Char(',') | Char('q'), &KeyModifiers::NONE) => {
+                    app.mode = Mode::Normal;
+                }
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
+                }
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
+                }
+                (Char('G'), &KeyModifiers::SHIFT) => {
+                    self.table.select(self.table.items.len() - 1);
+                }
+                (Char('g'), &KeyModifiers::NONE) => {
+                    self.table.select(0);
+                }
+                (Enter | Char(' ') | Char('l') | Right, &KeyModifiers::NONE) => {
+                    if let Some(setting) = selected {
+                        setting.change(&mut self.draft, 1);
+                    }
+                }
+                (Char('h') | Left, &KeyModifiers::NONE) => {
+                    if let Some(setting) = selected {
+                        setting.change(&mut self.draft, -1);
+                    }
+                }
+                (Char('s'), &KeyModifiers::NONE) => {
+                    self.save(app);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+        Some(vec![
+            ("Enter, l, →", "Next value"),
+            ("h, ←", "Previous value"),
+            ("s", "Save to config"),
+            ("Esc, ,, q", "Cancel"),
+            ("j, ↓", "Down"),
+            ("k, ↑", "Up"),
+            ("g", "Top"),
+            ("G", "Bottom"),
+        ])
+    }
+}
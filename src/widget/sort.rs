@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
@@ -11,13 +11,14 @@ use crate::app::{App, LoadType, Mode};
 
 use super::{border_block, EnumIter, StatefulTable, Widget};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum Sort {
     Date,
     Downloads,
     Seeders,
     Leechers,
     Size,
+    Ratio, // Sorted after fetching, since nyaa can't sort by it
 }
 
 #[derive(PartialEq, Clone)]
@@ -34,6 +35,7 @@ impl EnumIter<Sort> for Sort {
             Sort::Seeders,
             Sort::Leechers,
             Sort::Size,
+            Sort::Ratio,
         ];
         SORTS.iter()
     }
@@ -47,6 +49,7 @@ impl ToString for Sort {
             Sort::Seeders => "Seeders".to_owned(),
             Sort::Leechers => "Leechers".to_owned(),
             Sort::Size => "Size".to_owned(),
+            Sort::Ratio => "Ratio".to_owned(),
         }
     }
 }
@@ -59,43 +62,87 @@ impl Sort {
             Sort::Seeders => "seeders".to_owned(),
             Sort::Leechers => "leechers".to_owned(),
             Sort::Size => "size".to_owned(),
+            Sort::Ratio => "seeders".to_owned(),
         }
     }
 }
 
+pub fn find_sort<S: Into<String>>(name: S) -> Option<Sort> {
+    let name = name.into();
+    Sort::iter()
+        .find(|s| s.to_string().eq_ignore_ascii_case(&name))
+        .cloned()
+}
+
 pub struct SortPopup {
-    pub table: StatefulTable<String>,
+    pub table: StatefulTable<Sort>,
     pub selected: Sort,
 }
 
 impl Default for SortPopup {
     fn default() -> Self {
         SortPopup {
-            table: StatefulTable::with_items(Sort::iter().map(|item| item.to_string()).collect()),
+            table: StatefulTable::with_items(Sort::iter().cloned().collect()),
             selected: Sort::Date,
         }
     }
 }
 
+impl SortPopup {
+    // Only list `sorts`, in that order. Sorts that aren't listed can still be used by clicking
+    // a column header
+    pub fn with_sorts(&mut self, sorts: &[Sort]) {
+        let sorts = match sorts.is_empty() {
+            true => Sort::iter().cloned().collect(),
+            false => sorts.to_vec(),
+        };
+        self.table = StatefulTable::with_items(sorts);
+    }
+
+    // Sort by `sort`, or reverse the direction if already sorted by it
+    pub fn sort_by(&mut self, app: &mut App, sort: Sort) {
+        app.ascending = self.selected == sort && !app.ascending;
+        if let Some(i) = self.table.items.iter().position(|s| *s == sort) {
+            self.table.select(i);
+        }
+        self.selected = sort;
+        app.mode = Mode::Loading(LoadType::Sorting);
+    }
+
+    // Most seeded first, the most common sort, without opening the popup
+    pub fn quick_sort(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('b'),
+            kind: KeyEventKind::Press,
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) = e
+        {
+            self.sort_by(app, Sort::Seeders);
+            app.ascending = false;
+        }
+    }
+}
+
 impl Widget for SortPopup {
     fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
         let buf = f.buffer_mut();
         let center = super::centered_rect(30, self.table.items.len() as u16 + 2, area);
         let clear = super::centered_rect(center.width + 2, center.height, area);
-        let items = self.table.items.iter().enumerate().map(|(i, item)| {
-            Row::new(vec![match i == self.selected.to_owned() as usize {
-                true => format!("  {}", item.to_owned()),
-                false => format!("   {}", item.to_owned()),
+        let items = self.table.items.iter().map(|item| {
+            Row::new(vec![match *item == self.selected {
+                true => format!("  {}", item.to_string()),
+                false => format!("   {}", item.to_string()),
             }])
         });
         let table = Table::new(items, [Constraint::Percentage(100)])
-            .block(border_block(app.theme, true).title(
-                match app.mode == Mode::Sort(SortDir::Asc) {
+            .block(
+                border_block(app, true).title(match app.mode == Mode::Sort(SortDir::Asc) {
                     true => "Sort Ascending",
                     false => "Sort Descending",
-                },
-            ))
-            .highlight_style(Style::default().bg(app.theme.hl_bg));
+                }),
+            )
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
         super::clear(clear, buf, app.theme.bg);
         table.render(center, buf, &mut self.table.state.to_owned());
     }
@@ -104,27 +151,33 @@ impl Widget for SortPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('q') => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc | Char('s') | Char('q'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
-                    if let Some(i) = Sort::iter().nth(self.table.state.selected().unwrap_or(0)) {
+                (Enter, &KeyModifiers::NONE) => {
+                    if let Some(i) = self
+                        .table
+                        .items
+                        .get(self.table.state.selected().unwrap_or(0))
+                    {
                         self.selected = i.to_owned();
                         app.ascending = app.mode == Mode::Sort(SortDir::Asc);
                         app.mode = Mode::Loading(LoadType::Sorting);
@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
@@ -40,8 +40,8 @@ impl Widget for SourcesPopup {
         });
         super::clear(clear, buf, app.theme.bg);
         let table = Table::new(items, [Constraint::Percentage(100)])
-            .block(border_block(app.theme, true).title("Source"))
-            .highlight_style(Style::default().bg(app.theme.hl_bg));
+            .block(border_block(app, true).title("Source"))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
         table.render(center, buf, &mut self.table.state.to_owned());
     }
 
@@ -49,26 +49,29 @@ impl Widget for SourcesPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('q') => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                // Opened with Ctrl-s, so close with it too
+                (Esc | Char('q'), &KeyModifiers::NONE) | (Char('s'), &KeyModifiers::CONTROL) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                (Enter, &KeyModifiers::NONE) => {
                     if let Some(i) = Sources::iter().nth(self.table.state.selected().unwrap_or(0)) {
                         app.src = *i;
                         app.mode = Mode::Loading(LoadType::Searching);
@@ -90,3 +93,41 @@ impl Widget for SourcesPopup {
         ])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::{
+        app::{App, Mode},
+        widget::Widget,
+    };
+
+    use super::SourcesPopup;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> Mode {
+        let mut app = App::default();
+        app.mode = Mode::Sources;
+        let e = Event::Key(KeyEvent::new(code, modifiers));
+        SourcesPopup::default().handle_event(&mut app, &e);
+        app.mode
+    }
+
+    #[test]
+    fn help_close_keys_close() {
+        let help = SourcesPopup::get_help().unwrap_or_default();
+        let (keys, _) = help
+            .iter()
+            .find(|(_, action)| *action == "Close")
+            .expect("no Close entry in help");
+        assert_eq!(*keys, "Esc, Ctrl-s, q");
+        assert!(press(KeyCode::Esc, KeyModifiers::NONE) == Mode::Normal);
+        assert!(press(KeyCode::Char('s'), KeyModifiers::CONTROL) == Mode::Normal);
+        assert!(press(KeyCode::Char('q'), KeyModifiers::NONE) == Mode::Normal);
+    }
+
+    #[test]
+    fn plain_s_stays_open() {
+        assert!(press(KeyCode::Char('s'), KeyModifiers::NONE) == Mode::Sources);
+    }
+}
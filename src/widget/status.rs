@@ -0,0 +1,44 @@
+use crossterm::event::Event;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize as _},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::App;
+
+use super::Widget;
+
+#[derive(Default)]
+pub struct StatusWidget;
+
+impl Widget for StatusWidget {
+    fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
+        let (state, color) = match (app.loading, app.last_request_ok) {
+            (true, _) => ("Loading…", app.theme.fg),
+            (false, None) => ("Ready", app.theme.fg),
+            (false, Some(true)) => ("Connected", app.theme.trusted),
+            (false, Some(false)) => ("Request failed", app.theme.remake),
+        };
+        let sep = Span::styled(" │ ", Style::new().fg(app.theme.border_color));
+        let line = Line::from(vec![
+            Span::raw(format!(" Source: {}", app.src.to_string())),
+            sep.clone(),
+            Span::raw(format!("Client: {}", app.client.to_string())),
+            sep.clone(),
+            Span::raw(format!("Page {}/{}", app.page, app.last_page)),
+            sep,
+            Span::styled(state, Style::new().fg(color)),
+        ]);
+        let p = Paragraph::new(line).bg(app.theme.bg).fg(app.theme.fg);
+        f.render_widget(p, area);
+    }
+
+    fn handle_event(&mut self, _app: &mut App, _e: &Event) {}
+
+    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+        None
+    }
+}
@@ -1,6 +1,6 @@
 use std::cmp::min;
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Margin, Rect},
     style::{Color, Style},
@@ -8,10 +8,22 @@ use ratatui::{
     Frame,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::app::{App, Mode};
 
 use super::{border_block, StatefulTable, Widget};
 
+// Set in the config to use instead of the theme's own border
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Border {
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+    None,
+}
+
 pub struct Theme {
     pub name: &'static str,
     pub bg: Color,
@@ -20,12 +32,80 @@ pub struct Theme {
     pub border_color: Color,
     pub border_focused_color: Color,
     pub hl_bg: Color,
+    pub hl_fg: Color,
     pub solid_bg: Color,
     pub solid_fg: Color,
     pub trusted: Color,
     pub remake: Color,
+    pub recent: Color, // Dates of torrents posted within recent_hours
+    pub category_colors: &'static [(usize, Color)], // Category id and the color of its badge
+}
+
+impl Theme {
+    pub fn category_color(&self, id: usize) -> Color {
+        self.category_colors
+            .iter()
+            .find(|(cat, _)| *cat == id)
+            .map(|(_, color)| *color)
+            .unwrap_or(Color::Gray)
+    }
 }
 
+pub static DEFAULT_CATEGORY_COLORS: &[(usize, Color)] = &[
+    (0, Color::White),
+    (10, Color::Gray),
+    (11, Color::Magenta),
+    (12, Color::LightMagenta),
+    (13, Color::LightGreen),
+    (14, Color::Gray),
+    (20, Color::Gray),
+    (21, Color::Red),
+    (22, Color::Yellow),
+    (30, Color::Gray),
+    (31, Color::LightGreen),
+    (32, Color::Yellow),
+    (33, Color::Green),
+    (40, Color::Gray),
+    (41, Color::Yellow),
+    (42, Color::LightYellow),
+    (43, Color::LightCyan),
+    (44, Color::Gray),
+    (50, Color::Gray),
+    (51, Color::LightMagenta),
+    (52, Color::Magenta),
+    (60, Color::Gray),
+    (61, Color::Blue),
+    (62, Color::LightBlue),
+];
+
+// Okabe-Ito palette, which stays distinguishable with the common kinds of color blindness
+static COLORBLIND_CATEGORY_COLORS: &[(usize, Color)] = &[
+    (0, Color::White),
+    (10, Color::Gray),
+    (11, Color::Rgb(204, 121, 167)),
+    (12, Color::Rgb(86, 180, 233)),
+    (13, Color::Rgb(0, 158, 115)),
+    (14, Color::Gray),
+    (20, Color::Gray),
+    (21, Color::Rgb(213, 94, 0)),
+    (22, Color::Rgb(240, 228, 66)),
+    (30, Color::Gray),
+    (31, Color::Rgb(0, 158, 115)),
+    (32, Color::Rgb(240, 228, 66)),
+    (33, Color::Rgb(0, 114, 178)),
+    (40, Color::Gray),
+    (41, Color::Rgb(240, 228, 66)),
+    (42, Color::Rgb(204, 121, 167)),
+    (43, Color::Rgb(86, 180, 233)),
+    (44, Color::Gray),
+    (50, Color::Gray),
+    (51, Color::Rgb(204, 121, 167)),
+    (52, Color::Rgb(230, 159, 0)),
+    (60, Color::Gray),
+    (61, Color::Rgb(0, 114, 178)),
+    (62, Color::Rgb(86, 180, 233)),
+];
+
 pub static THEMES: &[&Theme] = &[
     &Theme {
         name: "Default",
@@ -35,10 +115,13 @@ pub static THEMES: &[&Theme] = &[
         border_color: Color::White,
         border_focused_color: Color::LightCyan,
         hl_bg: Color::DarkGray,
+        hl_fg: Color::White,
         solid_bg: Color::White,
         solid_fg: Color::Black,
         trusted: Color::Green,
         remake: Color::Red,
+        recent: Color::Yellow,
+        category_colors: DEFAULT_CATEGORY_COLORS,
     },
     &Theme {
         name: "Dracula",
@@ -48,10 +131,13 @@ pub static THEMES: &[&Theme] = &[
         border_color: Color::Rgb(98, 114, 164),
         border_focused_color: Color::Rgb(189, 147, 249),
         hl_bg: Color::Rgb(98, 114, 164),
+        hl_fg: Color::Rgb(248, 248, 242),
         solid_fg: Color::Rgb(40, 42, 54),
         solid_bg: Color::Rgb(139, 233, 253),
         trusted: Color::Rgb(80, 250, 123),
         remake: Color::Rgb(255, 85, 85),
+        recent: Color::Rgb(241, 250, 140),
+        category_colors: DEFAULT_CATEGORY_COLORS,
     },
     &Theme {
         name: "Gruvbox",
@@ -61,10 +147,13 @@ pub static THEMES: &[&Theme] = &[
         border_color: Color::Rgb(102, 92, 84),
         border_focused_color: Color::Rgb(214, 93, 14),
         hl_bg: Color::Rgb(80, 73, 69),
+        hl_fg: Color::Rgb(235, 219, 178),
         solid_bg: Color::Rgb(69, 133, 136),
         solid_fg: Color::Rgb(235, 219, 178),
         trusted: Color::Rgb(152, 151, 26),
         remake: Color::Rgb(204, 36, 29),
+        recent: Color::Rgb(215, 153, 33),
+        category_colors: DEFAULT_CATEGORY_COLORS,
     },
     &Theme {
         name: "Catppuccin Macchiato",
@@ -74,10 +163,29 @@ pub static THEMES: &[&Theme] = &[
         border_color: Color::Rgb(110, 115, 141),
         border_focused_color: Color::Rgb(125, 196, 228),
         hl_bg: Color::Rgb(110, 115, 141),
+        hl_fg: Color::Rgb(202, 211, 245),
         solid_bg: Color::Rgb(166, 218, 149),
         solid_fg: Color::Rgb(24, 25, 38),
         trusted: Color::Rgb(166, 218, 149),
         remake: Color::Rgb(237, 135, 150),
+        recent: Color::Rgb(238, 212, 159),
+        category_colors: DEFAULT_CATEGORY_COLORS,
+    },
+    &Theme {
+        name: "Colorblind",
+        bg: Color::Reset,
+        fg: Color::White,
+        border: BorderType::Plain,
+        border_color: Color::Gray,
+        border_focused_color: Color::Rgb(86, 180, 233),
+        hl_bg: Color::DarkGray,
+        hl_fg: Color::White,
+        solid_bg: Color::Rgb(86, 180, 233),
+        solid_fg: Color::Black,
+        trusted: Color::Rgb(86, 180, 233),
+        remake: Color::Rgb(230, 159, 0),
+        recent: Color::Rgb(240, 228, 66),
+        category_colors: COLORBLIND_CATEGORY_COLORS,
     },
 ];
 
@@ -107,6 +215,38 @@ impl Default for ThemePopup {
     }
 }
 
+impl ThemePopup {
+    fn select(&mut self, app: &mut App, i: usize) {
+        if let Some(theme) = THEMES.get(i) {
+            self.selected = i;
+            app.theme = theme;
+            app.config.default_theme = theme.name.to_owned();
+            if let Err(e) = app.config.store_keys(&["default_theme"]) {
+                app.show_error(format!(
+                    "Failed to update default theme in config file:\n{}",
+                    e
+                ));
+            }
+        }
+    }
+
+    // Go to the next theme without opening the popup, wrapping around
+    pub fn quick_switch(&mut self, app: &mut App, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('t'),
+            kind: KeyEventKind::Press,
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) = e
+        {
+            let i = (self.selected + 1) % THEMES.len();
+            self.select(app, i);
+            self.table.select(i);
+            app.notify(format!("Theme: {}", app.theme.name));
+        }
+    }
+}
+
 impl Widget for ThemePopup {
     fn draw(&self, f: &mut Frame, app: &App, area: Rect) {
         let buf = f.buffer_mut();
@@ -123,8 +263,8 @@ impl Widget for ThemePopup {
             ])
         });
         let table = Table::new(items, [Constraint::Percentage(100)])
-            .block(border_block(app.theme, true).title("Theme"))
-            .highlight_style(Style::default().bg(app.theme.hl_bg));
+            .block(border_block(app, true).title("Theme"))
+            .highlight_style(Style::default().bg(app.theme.hl_bg).fg(app.theme.hl_fg));
         super::clear(clear, buf, app.theme.bg);
         table.render(center, buf, &mut self.table.state.to_owned());
 
@@ -147,37 +287,29 @@ impl Widget for ThemePopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('t') | KeyCode::Char('q') => {
+            use KeyCode::*;
+            match (code, modifiers) {
+                (Esc | Char('t') | Char('q'), &KeyModifiers::NONE) => {
                     app.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.table.next_wrap(1);
+                (Char('j') | Down, &KeyModifiers::NONE) => {
+                    self.table.next_nav(1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.table.next_wrap(-1);
+                (Char('k') | Up, &KeyModifiers::NONE) => {
+                    self.table.next_nav(-1, app.config.wrap_navigation);
                 }
-                KeyCode::Char('G') => {
+                (Char('G'), &KeyModifiers::SHIFT) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                (Char('g'), &KeyModifiers::NONE) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
-                    if let Some(theme) = THEMES.get(self.table.state.selected().unwrap_or(0)) {
-                        self.selected = self.table.state.selected().unwrap_or(0);
-                        app.theme = theme;
-                        app.config.default_theme = theme.name.to_owned();
-                        if let Err(e) = app.config.clone().store() {
-                            app.show_error(format!(
-                                "Failed to update default theme in config file:\n{}",
-                                e
-                            ));
-                        }
-                    }
+                (Enter, &KeyModifiers::NONE) => {
+                    self.select(app, self.table.state.selected().unwrap_or(0));
                 }
                 _ => {}
             }